@@ -0,0 +1,450 @@
+//! H.264 encoder backends
+//!
+//! This module provides:
+//! - NVENC (`h264_nvenc`) and software (`libx264`) encoder backends
+//! - Equivalent quality presets per `ContentType` for each backend
+//! - Automatic backend selection with fallback to software encoding
+//! - Lazy selection, so GPU and encoder probing only happens for conversions
+//! - ffmpeg capability probing (encoders, decoders, filters) for diagnostics
+//!
+//! Only the NVENC backend needs an NVIDIA GPU; `libx264` works anywhere
+//! ffmpeg was built with it, which makes conversion usable on CI runners
+//! and laptops.
+
+use crate::{check_hardware_support, ContentType, VideoError};
+use colored::*;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::process::Command;
+use tracing::{debug, info, instrument, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncoderBackend {
+    /// NVIDIA hardware encoding with CUDA decoding
+    Nvenc,
+    /// Software encoding with x264
+    Libx264,
+}
+
+/// Which backend to use for conversion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EncoderPreference {
+    /// NVENC when a GPU is available, otherwise libx264
+    #[default]
+    Auto,
+    Nvenc,
+    Libx264,
+}
+
+impl EncoderBackend {
+    /// ffmpeg encoder name passed to `-c:v`
+    pub fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            EncoderBackend::Nvenc => "h264_nvenc",
+            EncoderBackend::Libx264 => "libx264",
+        }
+    }
+
+    /// Verify the backend can be used on this machine; the GPU is only checked for NVENC
+    #[instrument]
+    pub fn check_available(&self) -> Result<(), VideoError> {
+        match self {
+            EncoderBackend::Nvenc => check_hardware_support(),
+            EncoderBackend::Libx264 => {
+                if ffmpeg_has_encoder(self.ffmpeg_name())? {
+                    Ok(())
+                } else {
+                    Err(VideoError::FFmpeg(
+                        "libx264 encoder not available in this ffmpeg build".into(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Input options for hardware decoding, placed before `-i`
+    pub fn hwaccel_args(&self) -> Vec<String> {
+        match self {
+            EncoderBackend::Nvenc => vec!["-hwaccel".to_string(), "cuda".to_string()],
+            EncoderBackend::Libx264 => Vec::new(),
+        }
+    }
+
+    /// Highest quality preset at the given constant quality level
+    pub fn max_quality_args(&self, quality: u8) -> Vec<String> {
+        let (preset, rate_control) = match self {
+            EncoderBackend::Nvenc => ("p7", "-cq"),
+            EncoderBackend::Libx264 => ("slower", "-crf"),
+        };
+        vec![
+            "-preset".to_string(),
+            preset.to_string(),
+            rate_control.to_string(),
+            quality.to_string(),
+        ]
+    }
+
+    /// Preset, rate control and tuning tailored to the content type
+    pub fn content_args(&self, content_type: &ContentType) -> Vec<String> {
+        let args: &[&str] = match (self, content_type) {
+            // Screen capture: focus on sharpness and detail
+            (EncoderBackend::Nvenc, ContentType::ScreenCapture | ContentType::Presentation) => &[
+                "-preset",
+                "p7",
+                "-cq",
+                "15",
+                "-temporal-aq",
+                "1",
+                "-rc-lookahead",
+                "32",
+            ],
+            (EncoderBackend::Libx264, ContentType::ScreenCapture | ContentType::Presentation) => {
+                &["-preset", "slower", "-crf", "15", "-rc-lookahead", "32"]
+            }
+            // Live action and vertical phone footage: balanced quality and efficiency
+            (EncoderBackend::Nvenc, ContentType::LiveAction | ContentType::Vertical) => &[
+                "-preset",
+                "p5",
+                "-cq",
+                "18",
+                "-spatial-aq",
+                "1",
+                "-temporal-aq",
+                "1",
+            ],
+            (EncoderBackend::Libx264, ContentType::LiveAction | ContentType::Vertical) => {
+                &["-preset", "medium", "-crf", "18", "-tune", "film"]
+            }
+            // Animation: preserve flat colors and sharp edges
+            (EncoderBackend::Nvenc, ContentType::Animation) => {
+                &["-preset", "p6", "-cq", "16", "-aq-mode", "3"]
+            }
+            (EncoderBackend::Libx264, ContentType::Animation) => {
+                &["-preset", "slow", "-crf", "16", "-tune", "animation"]
+            }
+            // Safe defaults
+            (EncoderBackend::Nvenc, ContentType::Unknown) => &["-preset", "p6", "-cq", "18"],
+            (EncoderBackend::Libx264, ContentType::Unknown) => &["-preset", "slow", "-crf", "18"],
+        };
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+}
+
+impl fmt::Display for EncoderBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.ffmpeg_name())
+    }
+}
+
+/// Pick an available backend, falling back to libx264 when NVENC is unusable
+#[instrument]
+pub fn select_encoder(preference: EncoderPreference) -> Result<EncoderBackend, VideoError> {
+    let backend = match preference {
+        EncoderPreference::Nvenc => EncoderBackend::Nvenc,
+        EncoderPreference::Libx264 => EncoderBackend::Libx264,
+        EncoderPreference::Auto => match EncoderBackend::Nvenc.check_available() {
+            Ok(()) => return Ok(EncoderBackend::Nvenc),
+            Err(e) => {
+                warn!(?e, "NVENC unavailable, falling back to libx264");
+                EncoderBackend::Libx264
+            }
+        },
+    };
+
+    backend.check_available()?;
+    info!("Using {} encoder", backend);
+    Ok(backend)
+}
+
+/// Probe that resolves a preference to an available backend
+pub type EncoderProbe = fn(EncoderPreference) -> Result<EncoderBackend, VideoError>;
+
+/// Backend chosen on first use, so runs that never convert never probe the GPU
+#[derive(Debug)]
+pub struct LazyEncoder {
+    preference: EncoderPreference,
+    selected: Option<EncoderBackend>,
+    probe: EncoderProbe,
+}
+
+impl LazyEncoder {
+    pub fn new(preference: EncoderPreference) -> Self {
+        Self::with_probe(preference, select_encoder)
+    }
+
+    /// Use `probe` instead of `select_encoder` to pick the backend
+    pub fn with_probe(preference: EncoderPreference, probe: EncoderProbe) -> Self {
+        LazyEncoder {
+            preference,
+            selected: None,
+            probe,
+        }
+    }
+
+    /// Selected backend, probing availability on the first call
+    pub fn get(&mut self) -> Result<EncoderBackend, VideoError> {
+        if let Some(backend) = self.selected {
+            return Ok(backend);
+        }
+        let backend = (self.probe)(self.preference)?;
+        self.selected = Some(backend);
+        Ok(backend)
+    }
+
+    /// Backend selected so far, without probing
+    pub fn selected(&self) -> Option<EncoderBackend> {
+        self.selected
+    }
+
+    /// Backend selected so far, or a guess from the preference without
+    /// probing; used to describe a conversion that has not run
+    pub fn provisional(&self) -> EncoderBackend {
+        self.selected.unwrap_or(match self.preference {
+            EncoderPreference::Nvenc => EncoderBackend::Nvenc,
+            EncoderPreference::Auto | EncoderPreference::Libx264 => EncoderBackend::Libx264,
+        })
+    }
+}
+
+/// Whether `ffmpeg -encoders` lists the named encoder
+pub fn ffmpeg_has_encoder(name: &str) -> Result<bool, VideoError> {
+    let found = parse_codec_listing(&ffmpeg_listing("-encoders")?).contains(name);
+    debug!(name, found, "ffmpeg encoder lookup");
+    Ok(found)
+}
+
+/// Whether `ffmpeg -filters` lists the named filter
+pub fn ffmpeg_has_filter(name: &str) -> Result<bool, VideoError> {
+    let found = parse_filter_listing(&ffmpeg_listing("-filters")?).contains(name);
+    debug!(name, found, "ffmpeg filter lookup");
+    Ok(found)
+}
+
+/// Encoders the pipeline can use for video and audio fixes
+pub const DIAGNOSTIC_ENCODERS: &[&str] = &[
+    "h264_nvenc",
+    "libx264",
+    "prores_ks",
+    "dnxhd",
+    "aac",
+    "alac",
+    "pcm_s16le",
+    "pcm_s24le",
+];
+
+/// Decoders for common source formats, including NVDEC (cuvid) variants
+pub const DIAGNOSTIC_DECODERS: &[&str] = &[
+    "h264",
+    "hevc",
+    "av1",
+    "vp9",
+    "prores",
+    "dnxhd",
+    "h264_cuvid",
+    "hevc_cuvid",
+];
+
+/// Filters used for scaling, HDR tonemapping, deinterlacing and loudness
+pub const DIAGNOSTIC_FILTERS: &[&str] = &[
+    "scale",
+    "zscale",
+    "tonemap",
+    "libplacebo",
+    "loudnorm",
+    "bwdif",
+    "yadif",
+    "minterpolate",
+];
+
+/// Encoders, decoders and filters compiled into the local ffmpeg
+#[derive(Debug, Clone, Default)]
+pub struct FfmpegCapabilities {
+    pub encoders: BTreeSet<String>,
+    pub decoders: BTreeSet<String>,
+    pub filters: BTreeSet<String>,
+}
+
+impl FfmpegCapabilities {
+    /// Query ffmpeg for its encoders, decoders and filters
+    #[instrument]
+    pub fn probe() -> Result<Self, VideoError> {
+        Ok(FfmpegCapabilities {
+            encoders: parse_codec_listing(&ffmpeg_listing("-encoders")?),
+            decoders: parse_codec_listing(&ffmpeg_listing("-decoders")?),
+            filters: parse_filter_listing(&ffmpeg_listing("-filters")?),
+        })
+    }
+
+    pub fn display(&self) {
+        println!("\n{}", "🧰 FFmpeg Capabilities".bright_blue().bold());
+        println!("{}", "=".repeat(60).bright_blue());
+
+        for (title, available, names) in [
+            ("Encoders:", &self.encoders, DIAGNOSTIC_ENCODERS),
+            ("Decoders:", &self.decoders, DIAGNOSTIC_DECODERS),
+            ("Filters:", &self.filters, DIAGNOSTIC_FILTERS),
+        ] {
+            println!("\n{}", title.bright_blue().bold());
+            for name in names {
+                if available.contains(*name) {
+                    println!("  {} {}", "✅".green(), name);
+                } else {
+                    println!("  {} {} {}", "❌".red(), name, "(not available)".dimmed());
+                }
+            }
+        }
+
+        println!("{}", "=".repeat(60).bright_blue());
+    }
+}
+
+fn ffmpeg_listing(flag: &str) -> Result<String, VideoError> {
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", flag])
+        .output()
+        .map_err(|e| VideoError::FFmpeg(e.to_string()))?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Names from `ffmpeg -encoders` / `-decoders` output, listed after the "------" separator
+pub fn parse_codec_listing(listing: &str) -> BTreeSet<String> {
+    listing
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("------"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Names from `ffmpeg -filters` output, e.g. " ... zscale  V->V  Apply resizing..."
+pub fn parse_filter_listing(listing: &str) -> BTreeSet<String> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().take(3).collect();
+            match fields[..] {
+                [_, name, io] if io.contains("->") => Some(name.to_string()),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_backend_names_and_hwaccel() {
+        assert_eq!(EncoderBackend::Nvenc.ffmpeg_name(), "h264_nvenc");
+        assert_eq!(EncoderBackend::Libx264.to_string(), "libx264");
+        assert_eq!(EncoderBackend::Nvenc.hwaccel_args(), ["-hwaccel", "cuda"]);
+        assert!(EncoderBackend::Libx264.hwaccel_args().is_empty());
+    }
+
+    #[test]
+    fn test_content_presets_per_backend() {
+        let content_types = [
+            ContentType::ScreenCapture,
+            ContentType::Presentation,
+            ContentType::LiveAction,
+            ContentType::Animation,
+            ContentType::Vertical,
+            ContentType::Unknown,
+        ];
+
+        for content_type in &content_types {
+            let nvenc = EncoderBackend::Nvenc.content_args(content_type).join(" ");
+            let x264 = EncoderBackend::Libx264.content_args(content_type).join(" ");
+            assert!(nvenc.contains("-cq"), "Failed for {:?}", content_type);
+            assert!(x264.contains("-crf"), "Failed for {:?}", content_type);
+            assert!(!x264.contains("-cq"), "Failed for {:?}", content_type);
+        }
+
+        assert_eq!(
+            EncoderBackend::Libx264
+                .content_args(&ContentType::Animation)
+                .join(" "),
+            "-preset slow -crf 16 -tune animation"
+        );
+        assert_eq!(
+            EncoderBackend::Nvenc.max_quality_args(18).join(" "),
+            "-preset p7 -cq 18"
+        );
+        assert_eq!(
+            EncoderBackend::Libx264.max_quality_args(18).join(" "),
+            "-preset slower -crf 18"
+        );
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_listings() {
+        let encoders = "Encoders:
+ V..... = Video
+ A..... = Audio
+ ------
+ V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC (codec h264)
+ V....D h264_nvenc           NVIDIA NVENC H.264 encoder (codec h264)
+ A....D pcm_s24le            PCM signed 24-bit little-endian
+";
+        let names = parse_codec_listing(encoders);
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            ["h264_nvenc", "libx264", "pcm_s24le"]
+        );
+
+        let filters = "Filters:
+  T.. = Timeline support
+  A = Audio input/output
+  | = Source or sink filter
+ ... loudnorm          A->A       EBU R128 loudness normalization
+ TSC tonemap           V->V       Conversion to/from different dynamic ranges.
+ ..C zscale            V->V       Apply resizing, colorspace and bit depth conversion.
+";
+        let names = parse_filter_listing(filters);
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            ["loudnorm", "tonemap", "zscale"]
+        );
+
+        assert!(parse_codec_listing("").is_empty());
+    }
+
+    #[test]
+    fn test_lazy_encoder_does_not_probe_until_used() {
+        static PROBES: AtomicUsize = AtomicUsize::new(0);
+        fn counting_probe(_: EncoderPreference) -> Result<EncoderBackend, VideoError> {
+            PROBES.fetch_add(1, Ordering::SeqCst);
+            Ok(EncoderBackend::Libx264)
+        }
+
+        let mut encoder = LazyEncoder::with_probe(EncoderPreference::Auto, counting_probe);
+        assert_eq!(encoder.selected(), None);
+        assert_eq!(PROBES.load(Ordering::SeqCst), 0);
+
+        // Probed once on first use, then cached
+        for _ in 0..3 {
+            assert_eq!(encoder.get().unwrap(), EncoderBackend::Libx264);
+        }
+        assert_eq!(PROBES.load(Ordering::SeqCst), 1);
+        assert_eq!(encoder.selected(), Some(EncoderBackend::Libx264));
+    }
+
+    #[test]
+    fn test_select_encoder() {
+        // Results depend on the local ffmpeg build and GPU, but a selected
+        // backend must always pass its own availability check
+        for preference in [
+            EncoderPreference::Auto,
+            EncoderPreference::Nvenc,
+            EncoderPreference::Libx264,
+        ] {
+            match select_encoder(preference) {
+                Ok(backend) => assert!(backend.check_available().is_ok()),
+                Err(e) => assert!(matches!(e, VideoError::FFmpeg(_) | VideoError::HWAccel(_))),
+            }
+        }
+    }
+}
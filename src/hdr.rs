@@ -0,0 +1,520 @@
+//! HDR format detection
+//!
+//! This module provides:
+//! - Parsing of ffprobe HDR side data (mastering display, content light level,
+//!   Dolby Vision configuration records, HDR10+ dynamic metadata)
+//! - Classification of a stream as SDR, HDR10, HDR10+, HLG or Dolby Vision
+//! - The HDR-to-SDR conversion filter chain used by the fixers (zscale
+//!   linearization, tone mapping, BT.709 primaries, dithering to 8-bit)
+//!
+//! ffprobe never reports HDR in `color_space`; the transfer characteristics
+//! (`color_transfer`) and side data are what identify an HDR stream.
+
+use crate::encoder::ffmpeg_has_filter;
+use crate::VideoError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+use tracing::{debug, instrument, warn};
+
+/// SMPTE ST 2084 perceptual quantizer, used by HDR10, HDR10+ and most Dolby Vision
+pub const TRANSFER_PQ: &str = "smpte2084";
+/// ARIB STD-B67 hybrid log-gamma
+pub const TRANSFER_HLG: &str = "arib-std-b67";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum HdrFormat {
+    #[default]
+    Sdr,
+    Hdr10,
+    Hdr10Plus,
+    Hlg,
+    DolbyVision,
+}
+
+impl HdrFormat {
+    /// Name used in `quality.hdr_restrictions`, `None` for SDR
+    pub fn standards_key(&self) -> Option<&'static str> {
+        match self {
+            HdrFormat::Sdr => None,
+            HdrFormat::Hdr10 => Some("hdr10"),
+            HdrFormat::Hdr10Plus => Some("hdr10+"),
+            HdrFormat::Hlg => Some("hlg"),
+            HdrFormat::DolbyVision => Some("dolby_vision"),
+        }
+    }
+
+    pub fn is_hdr(&self) -> bool {
+        *self != HdrFormat::Sdr
+    }
+}
+
+impl fmt::Display for HdrFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HdrFormat::Sdr => "SDR",
+            HdrFormat::Hdr10 => "HDR10",
+            HdrFormat::Hdr10Plus => "HDR10+",
+            HdrFormat::Hlg => "HLG",
+            HdrFormat::DolbyVision => "Dolby Vision",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Curve used to compress HDR highlights into SDR range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToneMapOperator {
+    /// Filmic curve that keeps shadow contrast; a safe default for mixed content
+    #[default]
+    Hable,
+    /// Leaves in-range values untouched and rolls off only the highlights
+    Mobius,
+    /// ITU-R BT.2390 EETF, rendered with libplacebo (mobius when ffmpeg lacks it)
+    Bt2390,
+}
+
+impl fmt::Display for ToneMapOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ToneMapOperator::Hable => "hable",
+            ToneMapOperator::Mobius => "mobius",
+            ToneMapOperator::Bt2390 => "BT.2390",
+        };
+        f.write_str(name)
+    }
+}
+
+/// SMPTE ST 2086 mastering display luminance, in nits
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MasteringDisplay {
+    pub max_luminance: f64,
+    pub min_luminance: f64,
+}
+
+/// CTA-861.3 content light levels, in nits
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContentLightLevel {
+    pub max_cll: u32,
+    pub max_fall: u32,
+}
+
+/// Dolby Vision decoder configuration record
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DolbyVisionConfig {
+    pub profile: u8,
+    pub level: u8,
+    /// Base layer compatibility: 1 = HDR10, 2 = SDR, 4 = HLG, 0 = none
+    pub bl_compatibility_id: u8,
+}
+
+/// HDR side data collected from the stream and its first frame
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HdrMetadata {
+    pub mastering_display: Option<MasteringDisplay>,
+    pub content_light_level: Option<ContentLightLevel>,
+    pub dolby_vision: Option<DolbyVisionConfig>,
+    /// SMPTE ST 2094-40 dynamic metadata present
+    pub hdr10_plus: bool,
+}
+
+impl HdrMetadata {
+    /// Merge an ffprobe `side_data_list` array into this metadata
+    pub fn merge_side_data(&mut self, side_data_list: &serde_json::Value) {
+        let Some(entries) = side_data_list.as_array() else {
+            return;
+        };
+
+        for entry in entries {
+            let side_data_type = entry["side_data_type"].as_str().unwrap_or_default();
+            match side_data_type {
+                "Mastering display metadata" => {
+                    if let (Some(max_luminance), Some(min_luminance)) = (
+                        number(&entry["max_luminance"]),
+                        number(&entry["min_luminance"]),
+                    ) {
+                        self.mastering_display = Some(MasteringDisplay {
+                            max_luminance,
+                            min_luminance,
+                        });
+                    }
+                }
+                "Content light level metadata" => {
+                    self.content_light_level = Some(ContentLightLevel {
+                        max_cll: number(&entry["max_content"]).unwrap_or(0.0) as u32,
+                        max_fall: number(&entry["max_average"]).unwrap_or(0.0) as u32,
+                    });
+                }
+                "DOVI configuration record" => {
+                    self.dolby_vision = Some(DolbyVisionConfig {
+                        profile: number(&entry["dv_profile"]).unwrap_or(0.0) as u8,
+                        level: number(&entry["dv_level"]).unwrap_or(0.0) as u8,
+                        bl_compatibility_id: number(&entry["dv_bl_signal_compatibility_id"])
+                            .unwrap_or(0.0) as u8,
+                    });
+                }
+                other if other.contains("SMPTE2094-40") => self.hdr10_plus = true,
+                _ => {}
+            }
+        }
+    }
+
+    /// Short description of the static metadata, e.g. "mastering 1000 nits, MaxCLL 1000, MaxFALL 400"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(dv) = &self.dolby_vision {
+            parts.push(format!("Dolby Vision profile {}.{}", dv.profile, dv.level));
+        }
+        if let Some(mastering) = &self.mastering_display {
+            parts.push(format!("mastering {:.0} nits", mastering.max_luminance));
+        }
+        if let Some(cll) = &self.content_light_level {
+            parts.push(format!("MaxCLL {}, MaxFALL {}", cll.max_cll, cll.max_fall));
+        }
+        if self.hdr10_plus {
+            parts.push("HDR10+ dynamic metadata".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// ffprobe reports side data values as integers or "num/den" rationals
+fn number(value: &serde_json::Value) -> Option<f64> {
+    if let Some(number) = value.as_f64() {
+        return Some(number);
+    }
+    let text = value.as_str()?;
+    match text.split_once('/') {
+        Some((num, den)) => {
+            let den: f64 = den.parse().ok()?;
+            (den != 0.0).then_some(num.parse::<f64>().ok()? / den)
+        }
+        None => text.parse().ok(),
+    }
+}
+
+/// Classify the dynamic range of a stream from its transfer characteristics and side data
+pub fn classify(color_transfer: &str, metadata: &HdrMetadata) -> HdrFormat {
+    if metadata.dolby_vision.is_some() {
+        return HdrFormat::DolbyVision;
+    }
+
+    match color_transfer.to_lowercase().as_str() {
+        TRANSFER_PQ if metadata.hdr10_plus => HdrFormat::Hdr10Plus,
+        TRANSFER_PQ => HdrFormat::Hdr10,
+        TRANSFER_HLG => HdrFormat::Hlg,
+        // Static HDR metadata on a stream with unspecified transfer still marks it as HDR10;
+        // an explicit SDR transfer wins over leftover ST 2086 metadata
+        "" | "unknown" | "unspecified"
+            if metadata.mastering_display.is_some() || metadata.content_light_level.is_some() =>
+        {
+            HdrFormat::Hdr10
+        }
+        _ => HdrFormat::Sdr,
+    }
+}
+
+/// Filter chain that converts the stream to 8-bit SDR Rec. 709 (yuv420p).
+///
+/// PQ and HLG sources are linearized, tone mapped in float RGB, moved to
+/// BT.709 primaries and dithered back to 8-bit. SDR sources with wide-gamut
+/// primaries only need the primaries converted. Untagged HDR streams are
+/// assumed to be BT.2020.
+pub fn sdr_conversion_filters(
+    color_space: &str,
+    color_transfer: &str,
+    color_primaries: &str,
+    format: HdrFormat,
+    operator: ToneMapOperator,
+) -> Vec<String> {
+    static LIBPLACEBO_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+    let source = SourceColor::new(color_space, color_transfer, color_primaries, format);
+    let libplacebo = operator == ToneMapOperator::Bt2390
+        && source.transfer.is_some()
+        && *LIBPLACEBO_AVAILABLE.get_or_init(|| ffmpeg_has_filter("libplacebo").unwrap_or(false));
+    if operator == ToneMapOperator::Bt2390 && source.transfer.is_some() && !libplacebo {
+        warn!("ffmpeg has no libplacebo filter; tone mapping with mobius instead of BT.2390");
+    }
+    conversion_chain(&source, operator, libplacebo)
+}
+
+/// Input colour description in zscale's vocabulary
+#[derive(Debug)]
+struct SourceColor {
+    matrix: &'static str,
+    primaries: &'static str,
+    /// HDR transfer function; `None` for SDR
+    transfer: Option<&'static str>,
+}
+
+impl SourceColor {
+    fn new(
+        color_space: &str,
+        color_transfer: &str,
+        color_primaries: &str,
+        format: HdrFormat,
+    ) -> Self {
+        let transfer = if color_transfer.eq_ignore_ascii_case(TRANSFER_HLG) {
+            Some(TRANSFER_HLG)
+        } else if format.is_hdr() {
+            Some(TRANSFER_PQ)
+        } else {
+            None
+        };
+        let matrix = match color_space.to_lowercase().as_str() {
+            "bt709" => "bt709",
+            "bt2020c" => "bt2020c",
+            _ => "bt2020nc",
+        };
+        let primaries = match color_primaries.to_lowercase().as_str() {
+            "smpte431" => "smpte431",
+            "smpte432" => "smpte432",
+            _ => "bt2020",
+        };
+        SourceColor {
+            matrix,
+            primaries,
+            transfer,
+        }
+    }
+}
+
+fn conversion_chain(
+    source: &SourceColor,
+    operator: ToneMapOperator,
+    libplacebo: bool,
+) -> Vec<String> {
+    let Some(transfer) = source.transfer else {
+        // SDR: zscale converts the primaries in linear light internally
+        return vec![
+            format!(
+                "zscale=tin=bt709:min={}:pin={}:t=bt709:m=bt709:p=bt709:r=tv:d=error_diffusion",
+                source.matrix, source.primaries
+            ),
+            "format=yuv420p".to_string(),
+        ];
+    };
+
+    if libplacebo {
+        // libplacebo reads the input colour from frame tags, so set them explicitly
+        return vec![
+            format!(
+                "setparams=colorspace={}:color_primaries={}:color_trc={}",
+                source.matrix, source.primaries, transfer
+            ),
+            "libplacebo=tonemapping=bt.2390:colorspace=bt709:color_primaries=bt709:color_trc=bt709:range=tv:format=yuv420p".to_string(),
+        ];
+    }
+
+    let curve = match operator {
+        ToneMapOperator::Hable => "hable",
+        ToneMapOperator::Mobius | ToneMapOperator::Bt2390 => "mobius",
+    };
+    vec![
+        format!(
+            "zscale=tin={}:min={}:pin={}:t=linear:npl=100",
+            transfer, source.matrix, source.primaries
+        ),
+        "format=gbrpf32le".to_string(),
+        "zscale=p=bt709".to_string(),
+        format!("tonemap=tonemap={}:desat=0", curve),
+        "zscale=t=bt709:m=bt709:r=tv:d=error_diffusion".to_string(),
+        "format=yuv420p".to_string(),
+    ]
+}
+
+/// Read side data of the first video frame, where HEVC/AV1 streams carry
+/// mastering display, light level and HDR10+ metadata in-band
+#[instrument]
+pub fn probe_frame_side_data(path: &Path) -> Result<HdrMetadata, VideoError> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-read_intervals",
+            "%+#1",
+            "-show_frames",
+            "-show_entries",
+            "frame=side_data_list",
+            "-of",
+            "json",
+            path.to_str()
+                .ok_or_else(|| VideoError::InvalidPath(path.display().to_string()))?,
+        ])
+        .output()
+        .map_err(|e| VideoError::FFmpeg(format!("failed to run ffprobe: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(VideoError::FFmpeg(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| VideoError::FFmpeg(e.to_string()))?;
+
+    let mut metadata = HdrMetadata::default();
+    if let Some(frames) = json["frames"].as_array() {
+        for frame in frames {
+            metadata.merge_side_data(&frame["side_data_list"]);
+        }
+    }
+    debug!(?metadata, "frame HDR side data");
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_stream_side_data() {
+        let mut metadata = HdrMetadata::default();
+        metadata.merge_side_data(&json!([
+            {
+                "side_data_type": "Mastering display metadata",
+                "red_x": "34000/50000",
+                "max_luminance": "10000000/10000",
+                "min_luminance": "50/10000"
+            },
+            {
+                "side_data_type": "Content light level metadata",
+                "max_content": 1000,
+                "max_average": 400
+            },
+            { "side_data_type": "Display Matrix" }
+        ]));
+
+        assert_eq!(
+            metadata.mastering_display,
+            Some(MasteringDisplay {
+                max_luminance: 1000.0,
+                min_luminance: 0.005
+            })
+        );
+        assert_eq!(
+            metadata.content_light_level,
+            Some(ContentLightLevel {
+                max_cll: 1000,
+                max_fall: 400
+            })
+        );
+        assert!(metadata.dolby_vision.is_none());
+        assert_eq!(
+            metadata.summary(),
+            "mastering 1000 nits, MaxCLL 1000, MaxFALL 400"
+        );
+    }
+
+    #[test]
+    fn test_classify_hdr_formats() {
+        let sdr = HdrMetadata::default();
+        assert_eq!(classify("bt709", &sdr), HdrFormat::Sdr);
+        assert_eq!(classify("", &sdr), HdrFormat::Sdr);
+        assert_eq!(classify("smpte2084", &sdr), HdrFormat::Hdr10);
+        assert_eq!(classify("arib-std-b67", &sdr), HdrFormat::Hlg);
+
+        let mut hdr10_plus = HdrMetadata::default();
+        hdr10_plus.merge_side_data(&json!([
+            { "side_data_type": "HDR Dynamic Metadata SMPTE2094-40 (HDR10+)" }
+        ]));
+        assert_eq!(classify("smpte2084", &hdr10_plus), HdrFormat::Hdr10Plus);
+
+        let mut dolby_vision = HdrMetadata::default();
+        dolby_vision.merge_side_data(&json!([
+            {
+                "side_data_type": "DOVI configuration record",
+                "dv_profile": 8,
+                "dv_level": 6,
+                "dv_bl_signal_compatibility_id": 1
+            }
+        ]));
+        assert_eq!(classify("smpte2084", &dolby_vision), HdrFormat::DolbyVision);
+        assert_eq!(
+            dolby_vision.dolby_vision,
+            Some(DolbyVisionConfig {
+                profile: 8,
+                level: 6,
+                bl_compatibility_id: 1
+            })
+        );
+
+        let static_only = HdrMetadata {
+            content_light_level: Some(ContentLightLevel {
+                max_cll: 1000,
+                max_fall: 400,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(classify("unknown", &static_only), HdrFormat::Hdr10);
+        assert_eq!(classify("", &static_only), HdrFormat::Hdr10);
+
+        // SDR master carrying leftover mastering display metadata
+        let sdr_with_mastering = HdrMetadata {
+            mastering_display: Some(MasteringDisplay {
+                max_luminance: 1000.0,
+                min_luminance: 0.005,
+            }),
+            ..static_only
+        };
+        assert_eq!(classify("bt709", &sdr_with_mastering), HdrFormat::Sdr);
+    }
+
+    #[test]
+    fn test_hdr_format_standards_keys() {
+        assert_eq!(HdrFormat::Sdr.standards_key(), None);
+        assert_eq!(HdrFormat::Hdr10Plus.standards_key(), Some("hdr10+"));
+        assert_eq!(HdrFormat::DolbyVision.standards_key(), Some("dolby_vision"));
+        assert_eq!(HdrFormat::Hlg.to_string(), "HLG");
+        assert!(!HdrFormat::Sdr.is_hdr());
+    }
+
+    #[test]
+    fn test_tone_mapping_chain() {
+        let pq = SourceColor::new("bt2020nc", "smpte2084", "bt2020", HdrFormat::Hdr10);
+        assert_eq!(
+            conversion_chain(&pq, ToneMapOperator::Hable, false),
+            vec![
+                "zscale=tin=smpte2084:min=bt2020nc:pin=bt2020:t=linear:npl=100",
+                "format=gbrpf32le",
+                "zscale=p=bt709",
+                "tonemap=tonemap=hable:desat=0",
+                "zscale=t=bt709:m=bt709:r=tv:d=error_diffusion",
+                "format=yuv420p",
+            ]
+        );
+
+        // HLG is linearized with its own transfer function, even under Dolby Vision
+        let hlg = SourceColor::new("", "arib-std-b67", "", HdrFormat::DolbyVision);
+        let chain = conversion_chain(&hlg, ToneMapOperator::Mobius, false);
+        assert!(chain[0].starts_with("zscale=tin=arib-std-b67:min=bt2020nc:pin=bt2020"));
+        assert_eq!(chain[3], "tonemap=tonemap=mobius:desat=0");
+
+        // BT.2390 goes through libplacebo when available, otherwise mobius
+        let chain = conversion_chain(&pq, ToneMapOperator::Bt2390, true);
+        assert_eq!(
+            chain[0],
+            "setparams=colorspace=bt2020nc:color_primaries=bt2020:color_trc=smpte2084"
+        );
+        assert!(chain[1].starts_with("libplacebo=tonemapping=bt.2390"));
+        let chain = conversion_chain(&pq, ToneMapOperator::Bt2390, false);
+        assert_eq!(chain[3], "tonemap=tonemap=mobius:desat=0");
+
+        // Wide-gamut SDR only has its primaries converted
+        let p3 = SourceColor::new("bt709", "bt709", "smpte432", HdrFormat::Sdr);
+        assert_eq!(
+            conversion_chain(&p3, ToneMapOperator::Hable, true),
+            vec![
+                "zscale=tin=bt709:min=bt709:pin=smpte432:t=bt709:m=bt709:p=bt709:r=tv:d=error_diffusion",
+                "format=yuv420p",
+            ]
+        );
+    }
+}
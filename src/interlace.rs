@@ -0,0 +1,239 @@
+//! Interlacing detection
+//!
+//! This module provides:
+//! - Field order as signalled by the stream (`field_order` in ffprobe)
+//! - An optional `idet` pass that classifies decoded frames, catching
+//!   interlaced video flagged as progressive (and the reverse)
+//! - The deinterlacing filter used by the fixers (bwdif, or yadif on older ffmpeg)
+
+use crate::encoder::ffmpeg_has_filter;
+use crate::VideoError;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+use tracing::{debug, instrument};
+
+/// Frames decoded by the `idet` pass; enough to get past intros and fades
+pub const IDET_FRAMES: u32 = 500;
+
+/// Field order signalled in the stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum FieldOrder {
+    Progressive,
+    TopFieldFirst,
+    BottomFieldFirst,
+    #[default]
+    Unknown,
+}
+
+impl FieldOrder {
+    /// Parse ffprobe's `field_order`: "progressive", "tt", "bb", "tb" or "bt".
+    /// The second letter is the field displayed first.
+    pub fn from_ffprobe(field_order: &str) -> Self {
+        match field_order {
+            "progressive" => FieldOrder::Progressive,
+            "tt" | "bt" => FieldOrder::TopFieldFirst,
+            "bb" | "tb" => FieldOrder::BottomFieldFirst,
+            _ => FieldOrder::Unknown,
+        }
+    }
+
+    pub fn is_interlaced(&self) -> bool {
+        matches!(
+            self,
+            FieldOrder::TopFieldFirst | FieldOrder::BottomFieldFirst
+        )
+    }
+
+    /// Value for the `parity` option of bwdif/yadif
+    fn parity(&self) -> &'static str {
+        match self {
+            FieldOrder::TopFieldFirst => "tff",
+            FieldOrder::BottomFieldFirst => "bff",
+            _ => "auto",
+        }
+    }
+}
+
+impl fmt::Display for FieldOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FieldOrder::Progressive => "progressive",
+            FieldOrder::TopFieldFirst => "top field first",
+            FieldOrder::BottomFieldFirst => "bottom field first",
+            FieldOrder::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Multi-frame classification counts from the `idet` filter
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct InterlaceAnalysis {
+    pub tff: u32,
+    pub bff: u32,
+    pub progressive: u32,
+    pub undetermined: u32,
+}
+
+impl InterlaceAnalysis {
+    /// Interlaced when most frames idet could classify are interlaced
+    pub fn is_interlaced(&self) -> bool {
+        self.tff + self.bff > self.progressive
+    }
+
+    /// The dominant field order among interlaced frames
+    pub fn field_order(&self) -> FieldOrder {
+        if !self.is_interlaced() {
+            FieldOrder::Progressive
+        } else if self.tff >= self.bff {
+            FieldOrder::TopFieldFirst
+        } else {
+            FieldOrder::BottomFieldFirst
+        }
+    }
+
+    /// One-line summary, e.g. "idet: TFF 480, BFF 0, progressive 12, undetermined 8"
+    pub fn summary(&self) -> String {
+        format!(
+            "idet: TFF {}, BFF {}, progressive {}, undetermined {}",
+            self.tff, self.bff, self.progressive, self.undetermined
+        )
+    }
+}
+
+/// Parse the "Multi frame detection" line that `idet` logs when it finishes
+pub fn parse_idet_output(stderr: &str) -> Option<InterlaceAnalysis> {
+    let line = stderr
+        .lines()
+        .rev()
+        .find(|line| line.contains("Multi frame detection:"))?;
+    let (_, counts) = line.split_once("Multi frame detection:")?;
+
+    let mut analysis = InterlaceAnalysis::default();
+    let tokens: Vec<&str> = counts.split_whitespace().collect();
+    for pair in tokens.chunks(2) {
+        let [label, count] = pair else {
+            continue;
+        };
+        let count: u32 = count.parse().ok()?;
+        match *label {
+            "TFF:" => analysis.tff = count,
+            "BFF:" => analysis.bff = count,
+            "Progressive:" => analysis.progressive = count,
+            "Undetermined:" => analysis.undetermined = count,
+            _ => {}
+        }
+    }
+    Some(analysis)
+}
+
+/// Decode the first `frames` frames through `idet` to see whether the picture is interlaced
+#[instrument]
+pub fn detect_interlacing(path: &Path, frames: u32) -> Result<InterlaceAnalysis, VideoError> {
+    let output = Command::new("ffmpeg")
+        .args([
+            "-hide_banner",
+            "-nostats",
+            "-i",
+            path.to_str()
+                .ok_or_else(|| VideoError::InvalidPath(path.display().to_string()))?,
+            "-map",
+            "0:v:0",
+            "-frames:v",
+            &frames.to_string(),
+            "-vf",
+            "idet",
+            "-an",
+            "-f",
+            "null",
+            "-",
+        ])
+        .output()
+        .map_err(|e| VideoError::FFmpeg(format!("failed to run ffmpeg: {}", e)))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(VideoError::FFmpeg(stderr.to_string()));
+    }
+
+    let analysis = parse_idet_output(&stderr)
+        .ok_or_else(|| VideoError::FFmpeg("idet reported no frame statistics".to_string()))?;
+    debug!(?analysis, "idet analysis");
+    Ok(analysis)
+}
+
+/// Deinterlacing filter for the given field order. bwdif is preferred; yadif,
+/// which takes the same options, is used when ffmpeg was built without bwdif.
+pub fn deinterlace_filter(field_order: FieldOrder) -> String {
+    static BWDIF_AVAILABLE: OnceLock<bool> = OnceLock::new();
+    // Assume bwdif when ffmpeg cannot be queried; the conversion itself will report that
+    let bwdif = *BWDIF_AVAILABLE.get_or_init(|| ffmpeg_has_filter("bwdif").unwrap_or(true));
+
+    format!(
+        "{}=mode=send_frame:parity={}:deint=all",
+        if bwdif { "bwdif" } else { "yadif" },
+        field_order.parity()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_order_from_ffprobe() {
+        assert_eq!(
+            FieldOrder::from_ffprobe("progressive"),
+            FieldOrder::Progressive
+        );
+        assert_eq!(FieldOrder::from_ffprobe("tt"), FieldOrder::TopFieldFirst);
+        assert_eq!(FieldOrder::from_ffprobe("tb"), FieldOrder::BottomFieldFirst);
+        assert_eq!(FieldOrder::from_ffprobe(""), FieldOrder::Unknown);
+        assert!(FieldOrder::BottomFieldFirst.is_interlaced());
+        assert!(!FieldOrder::Unknown.is_interlaced());
+        assert_eq!(FieldOrder::TopFieldFirst.to_string(), "top field first");
+    }
+
+    #[test]
+    fn test_parse_idet_output() {
+        let stderr = "\
+[Parsed_idet_0 @ 0x5581] Repeated Fields: Neither:   500 Top:     0 Bottom:     0
+[Parsed_idet_0 @ 0x5581] Single frame detection: TFF:   301 BFF:     0 Progressive:    97 Undetermined:   102
+[Parsed_idet_0 @ 0x5581] Multi frame detection: TFF:   480 BFF:     0 Progressive:    12 Undetermined:     8
+";
+        let analysis = parse_idet_output(stderr).unwrap();
+        assert_eq!(
+            analysis,
+            InterlaceAnalysis {
+                tff: 480,
+                bff: 0,
+                progressive: 12,
+                undetermined: 8
+            }
+        );
+        assert!(analysis.is_interlaced());
+        assert_eq!(analysis.field_order(), FieldOrder::TopFieldFirst);
+        assert_eq!(
+            analysis.summary(),
+            "idet: TFF 480, BFF 0, progressive 12, undetermined 8"
+        );
+
+        let progressive = InterlaceAnalysis {
+            progressive: 500,
+            ..Default::default()
+        };
+        assert!(!progressive.is_interlaced());
+        assert_eq!(progressive.field_order(), FieldOrder::Progressive);
+        assert!(parse_idet_output("no statistics").is_none());
+    }
+
+    #[test]
+    fn test_deinterlace_filter_options() {
+        let filter = deinterlace_filter(FieldOrder::BottomFieldFirst);
+        assert!(filter.ends_with("=mode=send_frame:parity=bff:deint=all"));
+        assert!(filter.starts_with("bwdif") || filter.starts_with("yadif"));
+    }
+}
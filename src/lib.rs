@@ -3,6 +3,7 @@
 use colored::*;
 use humansize::{format_size, DECIMAL};
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -12,8 +13,28 @@ use thiserror::Error;
 use tracing::{debug, error, info, instrument, warn};
 use tracing_subscriber::EnvFilter;
 
+// H.264 encoder backends
+pub mod encoder;
 // Google Drive integration
 pub mod google_drive;
+// HDR format detection
+pub mod hdr;
+// Interlacing detection
+pub mod interlace;
+// Named standards profiles
+pub mod profiles;
+// MP4/MOV atom layout
+pub mod quicktime;
+// Typed video resolutions
+pub mod resolution;
+// Packet-level stream analysis
+pub mod stream_analysis;
+
+use encoder::{EncoderBackend, EncoderPreference, LazyEncoder};
+use hdr::{HdrFormat, HdrMetadata, ToneMapOperator};
+use interlace::{FieldOrder, InterlaceAnalysis};
+use resolution::{format_resolutions, Orientation, Resolution, ScalingPolicy};
+use stream_analysis::{AudioBitrateAnalysis, FrameRateAnalysis, GopAnalysis};
 
 pub fn init_logging() {
     tracing_subscriber::fmt()
@@ -31,22 +52,35 @@ impl ContentStandards {
 
         let video = VideoStandards {
             preferred_resolutions: vec![
-                "1280x720".to_string(),
-                "1920x1080".to_string(),
-                "720x1280".to_string(),  // Vertical
-                "1080x1920".to_string(), // Vertical HD
+                Resolution::new(1280, 720),
+                Resolution::new(1920, 1080),
+                Resolution::new(720, 1280),  // Vertical
+                Resolution::new(1080, 1920), // Vertical HD
             ],
             acceptable_resolutions: vec![
-                "1360x768".to_string(),
-                "1280x800".to_string(),
-                "1600x900".to_string(),
-                "1440x900".to_string(),
-                "1680x1048".to_string(),
-                "1440x810".to_string(),
-                "2160x3840".to_string(), // Vertical 4K
+                Resolution::new(1360, 768),
+                Resolution::new(1280, 800),
+                Resolution::new(1600, 900),
+                Resolution::new(1440, 900),
+                Resolution::new(1680, 1048),
+                Resolution::new(1440, 810),
+                Resolution::new(2160, 3840), // Vertical 4K
             ],
+            scaling_policy: ScalingPolicy::Pad,
             preferred_codecs: vec!["h264".to_string(), "libx264".to_string()],
             preferred_frame_rates: vec![15.0, 23.976, 24.0, 25.0, 29.97, 30.0],
+            frame_rate_conversion: FrameRateConversion::Fps,
+            content_frame_rates: HashMap::from([
+                ("screen_capture".to_string(), vec![15.0, 29.97, 30.0]),
+                (
+                    "live_action".to_string(),
+                    vec![23.976, 24.0, 25.0, 29.97, 30.0],
+                ),
+                (
+                    "vertical".to_string(),
+                    vec![23.976, 24.0, 25.0, 29.97, 30.0],
+                ),
+            ]),
             bitrate_ranges: HashMap::from([
                 (
                     "screen_capture".to_string(),
@@ -68,11 +102,15 @@ impl ContentStandards {
             containers: vec!["mp4".to_string(), "mov".to_string()],
             unsupported_containers: vec!["mkv".to_string()],
             profiles: vec!["main".to_string(), "high".to_string()],
+            // Level 5.1 covers the largest allowed resolution (2160x3840 at 30 fps)
+            max_levels: HashMap::from([("main".to_string(), 5.1), ("high".to_string(), 5.1)]),
+            aspect_ratios: default_aspect_ratios(),
         };
 
         let audio = AudioStandards {
             preferred_codecs: vec!["pcm".to_string(), "alac".to_string()],
             acceptable_codecs: vec!["aac".to_string()],
+            target_codec: AudioTarget::Pcm,
             sample_rates: vec![44100, 48000],
             bit_depths: vec![16, 24],
             bitrate_ranges: HashMap::from([("aac".to_string(), 320)]),
@@ -81,13 +119,17 @@ impl ContentStandards {
 
         let quality = QualityStandards {
             color_spaces: vec!["rec709".to_string(), "bt709".to_string()],
-            unsupported_color_spaces: vec![
+            // ffprobe color_primaries names; smpte431/432 are DCI-P3 and Display P3
+            unsupported_color_primaries: vec![
                 "bt2020".to_string(),
-                "dci-p3".to_string(),
-                "rec2020".to_string(),
+                "smpte431".to_string(),
+                "smpte432".to_string(),
             ],
             keyframe_interval_min: 2,
             chroma_subsampling: vec!["4:2:0".to_string(), "4:2:2".to_string()],
+            bit_depths: default_video_bit_depths(),
+            require_progressive: default_require_progressive(),
+            tone_mapping: ToneMapOperator::Hable,
             hdr_restrictions: vec![
                 "hdr10".to_string(),
                 "hdr10+".to_string(),
@@ -100,10 +142,490 @@ impl ContentStandards {
             video,
             audio,
             quality,
+            codec_formats: default_codec_formats(),
+        })
+    }
+
+    /// Format rules for an intermediate codec accepted in place of the preferred codecs
+    pub fn codec_format(&self, codec: &str) -> Option<&CodecFormat> {
+        self.codec_formats.get(codec)
+    }
+
+    /// Load standards from a TOML, YAML or JSON file, chosen by extension
+    pub fn load_from_path(path: &Path) -> Result<Self, VideoError> {
+        info!("Loading content delivery standards from {}", path.display());
+
+        let standards: Self = read_document(path)?;
+
+        standards
+            .validate()
+            .map_err(|e| VideoError::Standards(format!("{}: {}", path.display(), e)))?;
+
+        Ok(standards)
+    }
+
+    /// Check the standards for values the compliance engine cannot work with.
+    /// Errors name the offending key, e.g. `video.preferred_resolutions[1]`.
+    pub fn validate(&self) -> Result<(), String> {
+        let video = &self.video;
+
+        if video.preferred_resolutions.is_empty() {
+            return Err("video.preferred_resolutions: at least one resolution is required".into());
+        }
+        for (i, ratio) in video.aspect_ratios.iter().enumerate() {
+            if parse_ratio(ratio).is_none() {
+                return Err(format!(
+                    "video.aspect_ratios[{}]: '{}' is not a W:H aspect ratio",
+                    i, ratio
+                ));
+            }
+        }
+
+        if video.preferred_codecs.is_empty() {
+            return Err("video.preferred_codecs: at least one codec is required".into());
+        }
+
+        for (i, fps) in video.preferred_frame_rates.iter().enumerate() {
+            if !fps.is_finite() || *fps <= 0.0 {
+                return Err(format!(
+                    "video.preferred_frame_rates[{}]: frame rate must be positive, got {}",
+                    i, fps
+                ));
+            }
+        }
+
+        for (name, rates) in &video.content_frame_rates {
+            for (i, fps) in rates.iter().enumerate() {
+                if !fps.is_finite() || *fps <= 0.0 {
+                    return Err(format!(
+                        "video.content_frame_rates.{}[{}]: frame rate must be positive, got {}",
+                        name, i, fps
+                    ));
+                }
+            }
+        }
+
+        for (name, range) in &video.bitrate_ranges {
+            if range.min_kbps > range.max_kbps {
+                return Err(format!(
+                    "video.bitrate_ranges.{}: min_kbps ({}) exceeds max_kbps ({})",
+                    name, range.min_kbps, range.max_kbps
+                ));
+            }
+        }
+
+        for (profile, level) in &video.max_levels {
+            if !level.is_finite() || *level <= 0.0 {
+                return Err(format!(
+                    "video.max_levels.{}: level must be positive, got {}",
+                    profile, level
+                ));
+            }
+        }
+
+        if video.containers.is_empty() {
+            return Err("video.containers: at least one container is required".into());
+        }
+        if let Some(container) = video
+            .containers
+            .iter()
+            .find(|c| video.unsupported_containers.contains(c))
+        {
+            return Err(format!(
+                "video.unsupported_containers: '{}' is also listed in video.containers",
+                container
+            ));
+        }
+
+        if self.audio.preferred_codecs.is_empty() && self.audio.acceptable_codecs.is_empty() {
+            return Err("audio.preferred_codecs: at least one audio codec is required".into());
+        }
+        if let Some(i) = self.audio.sample_rates.iter().position(|rate| *rate == 0) {
+            return Err(format!(
+                "audio.sample_rates[{}]: sample rate must be positive",
+                i
+            ));
+        }
+        if let Some(i) = self.audio.bit_depths.iter().position(|depth| *depth == 0) {
+            return Err(format!(
+                "audio.bit_depths[{}]: bit depth must be positive",
+                i
+            ));
+        }
+
+        if let Some(i) = self.quality.bit_depths.iter().position(|depth| *depth == 0) {
+            return Err(format!(
+                "quality.bit_depths[{}]: bit depth must be positive",
+                i
+            ));
+        }
+
+        for (i, subsampling) in self.quality.chroma_subsampling.iter().enumerate() {
+            if !is_chroma_subsampling(subsampling) {
+                return Err(format!(
+                    "quality.chroma_subsampling[{}]: '{}' is not in J:a:b form (e.g. 4:2:0)",
+                    i, subsampling
+                ));
+            }
+        }
+
+        for (codec, format) in &self.codec_formats {
+            if format.containers.is_empty() {
+                return Err(format!(
+                    "codec_formats.{}.containers: at least one container is required",
+                    codec
+                ));
+            }
+            if format.bit_depths.is_empty() || format.bit_depths.contains(&0) {
+                return Err(format!(
+                    "codec_formats.{}.bit_depths: bit depths must be positive",
+                    codec
+                ));
+            }
+            for (i, subsampling) in format.chroma_subsampling.iter().enumerate() {
+                if !is_chroma_subsampling(subsampling) {
+                    return Err(format!(
+                        "codec_formats.{}.chroma_subsampling[{}]: '{}' is not in J:a:b form (e.g. 4:2:2)",
+                        codec, i, subsampling
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_chroma_subsampling(value: &str) -> bool {
+    let parts: Vec<&str> = value.split(':').collect();
+    parts.len() == 3 && parts.iter().all(|p| p.parse::<u8>().is_ok())
+}
+
+/// Read a TOML, YAML or JSON document, choosing the format by file extension.
+/// Errors are reported as `VideoError::Standards` prefixed with the file path.
+pub(crate) fn read_document<T: DeserializeOwned>(path: &Path) -> Result<T, VideoError> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        VideoError::Standards(format!("{}: unable to read file: {}", path.display(), e))
+    })?;
+
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    parse_document(&contents, &extension)
+        .map_err(|e| VideoError::Standards(format!("{}: {}", path.display(), e)))
+}
+
+/// Parse document text in the given format ("toml", "yaml"/"yml" or "json")
+pub(crate) fn parse_document<T: DeserializeOwned>(
+    contents: &str,
+    format: &str,
+) -> Result<T, String> {
+    match format {
+        "toml" => {
+            serde_path_to_error::deserialize(toml::Deserializer::new(contents)).map_err(path_error)
+        }
+        "yaml" | "yml" => {
+            serde_path_to_error::deserialize(serde_norway::Deserializer::from_str(contents))
+                .map_err(path_error)
+        }
+        "json" => {
+            serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(contents))
+                .map_err(path_error)
+        }
+        other => Err(format!(
+            "unsupported standards format '{}' (expected toml, yaml, yml or json)",
+            other
+        )),
+    }
+}
+
+/// Format a deserialization error with the key path it occurred at
+fn path_error<E: std::fmt::Display>(e: serde_path_to_error::Error<E>) -> String {
+    format!("{}: {}", e.path(), e.inner())
+}
+
+/// Compare a measured frame rate with a standard one. NTSC-style rates such as
+/// 23.976 and 29.97 are treated as their exact N*1000/1001 values, so 30000/1001
+/// matches 29.97 while 30 does not.
+pub fn frame_rate_matches(actual: f64, standard: f64) -> bool {
+    const TOLERANCE: f64 = 0.01;
+
+    let integer_rate = (standard * 1.001).round();
+    let canonical = if (standard - integer_rate).abs() > TOLERANCE
+        && (standard - integer_rate / 1.001).abs() < TOLERANCE
+    {
+        integer_rate * 1000.0 / 1001.0
+    } else {
+        standard
+    };
+
+    (actual - canonical).abs() <= TOLERANCE
+}
+
+/// Parse an ffprobe frame rate such as "30000/1001" or "25"; 0 when unknown ("0/0")
+fn parse_frame_rate(value: &serde_json::Value) -> f64 {
+    let Some(rate) = value.as_str() else {
+        return 0.0;
+    };
+    match parse_ratio(rate) {
+        Some((num, den)) => num as f64 / den as f64,
+        None => rate.parse().unwrap_or(0.0),
+    }
+}
+
+/// The allowed rate closest to a measured one
+fn nearest_frame_rate(actual: f64, rates: &[f64]) -> Option<f64> {
+    rates
+        .iter()
+        .copied()
+        .min_by(|a, b| (a - actual).abs().total_cmp(&(b - actual).abs()))
+}
+
+/// Allowed rate to convert an out-of-spec frame rate to. Rates the source is a
+/// whole multiple of win (60 -> 30, 50 -> 25), since frames are then simply
+/// dropped; otherwise the nearest allowed rate.
+fn conversion_frame_rate(source: f64, rates: &[f64]) -> Option<f64> {
+    rates
+        .iter()
+        .copied()
+        .filter(|rate| is_integer_ratio(source, *rate))
+        .max_by(|a, b| a.total_cmp(b))
+        .or_else(|| nearest_frame_rate(source, rates))
+}
+
+/// True when `source` is a whole multiple of `target`, e.g. 59.94 and 29.97
+/// (but not 59.94 and 30)
+fn is_integer_ratio(source: f64, target: f64) -> bool {
+    if target <= 0.0 || source <= target {
+        return false;
+    }
+    let ratio = (source / target).round();
+    frame_rate_matches(source / ratio, target)
+}
+
+/// Frame rate as an exact ffmpeg rational, e.g. 29.97 -> "30000/1001"
+fn frame_rate_expr(rate: f64) -> String {
+    let integer_rate = (rate * 1.001).round();
+    if (rate - integer_rate).abs() > 0.01 && frame_rate_matches(integer_rate / 1.001, rate) {
+        format!("{}/1001", integer_rate as u64 * 1000)
+    } else {
+        rate.to_string()
+    }
+}
+
+/// Derive chroma subsampling and bit depth from an ffmpeg pixel format name,
+/// e.g. "yuv422p10le" -> ("4:2:2", 10). RGB formats count as 4:4:4.
+pub fn parse_pixel_format(pix_fmt: &str) -> Option<(String, u8)> {
+    let pix_fmt = pix_fmt
+        .trim()
+        .to_lowercase()
+        .trim_end_matches("le")
+        .trim_end_matches("be")
+        .to_string();
+
+    // Packed and semi-planar formats without a naming pattern
+    let fixed = match pix_fmt.as_str() {
+        "nv12" | "nv21" => Some(("4:2:0", 8)),
+        "nv16" | "yuyv422" | "uyvy422" | "yvyu422" => Some(("4:2:2", 8)),
+        "nv24" | "nv42" => Some(("4:4:4", 8)),
+        "p010" => Some(("4:2:0", 10)),
+        "p012" => Some(("4:2:0", 12)),
+        "p016" => Some(("4:2:0", 16)),
+        "p210" | "y210" | "v210" => Some(("4:2:2", 10)),
+        "p410" => Some(("4:4:4", 10)),
+        _ => None,
+    };
+    if let Some((subsampling, depth)) = fixed {
+        return Some((subsampling.to_string(), depth));
+    }
+
+    let trailing_depth = |rest: &str| -> Option<u8> {
+        if rest.is_empty() {
+            Some(8)
+        } else {
+            rest.parse().ok()
+        }
+    };
+
+    // Planar YUV: yuv420p, yuvj422p, yuva444p10, ...
+    if let Some(rest) = pix_fmt
+        .strip_prefix("yuvj")
+        .or_else(|| pix_fmt.strip_prefix("yuva"))
+        .or_else(|| pix_fmt.strip_prefix("yuv"))
+    {
+        let (layout, depth) = rest.split_once('p')?;
+        let subsampling = match layout {
+            "420" => "4:2:0",
+            "422" => "4:2:2",
+            "444" => "4:4:4",
+            "440" => "4:4:0",
+            "411" => "4:1:1",
+            "410" => "4:1:0",
+            _ => return None,
+        };
+        return Some((subsampling.to_string(), trailing_depth(depth)?));
+    }
+
+    // Planar RGB: gbrp, gbrap12, ...
+    if let Some(rest) = pix_fmt
+        .strip_prefix("gbrap")
+        .or_else(|| pix_fmt.strip_prefix("gbrp"))
+    {
+        return Some(("4:4:4".to_string(), trailing_depth(rest)?));
+    }
+
+    // Grayscale has no chroma planes
+    if let Some(rest) = pix_fmt.strip_prefix("gray") {
+        return Some(("4:0:0".to_string(), trailing_depth(rest)?));
+    }
+
+    // Packed RGB: rgb24, bgr0, rgba, argb, rgb48, rgba64, ...
+    if ["rgb", "bgr", "argb", "abgr", "0rgb", "0bgr"]
+        .iter()
+        .any(|prefix| pix_fmt.starts_with(prefix))
+    {
+        let depth = if pix_fmt.ends_with("48") || pix_fmt.ends_with("64") {
+            16
+        } else {
+            8
+        };
+        return Some(("4:4:4".to_string(), depth));
+    }
+
+    None
+}
+
+/// Codec family used in the standards, e.g. "pcm_s24le" -> "pcm"
+pub fn audio_codec_family(codec: &str) -> &str {
+    if codec.starts_with("pcm_") {
+        "pcm"
+    } else {
+        codec
+    }
+}
+
+/// Normalized channel layout, e.g. "5.1(side)" -> "5.1"; falls back to the channel count
+fn channel_layout_name(channels: u32, layout: &str) -> String {
+    let layout = layout.trim().to_lowercase();
+    let layout = layout.split('(').next().unwrap_or_default();
+    if layout.is_empty() || layout == "unknown" {
+        match (channels, channel_count_designation(channels)) {
+            (1, _) => "mono".to_string(),
+            (_, Some(designation)) => designation.to_string(),
+            (n, None) => format!("{} channels", n),
+        }
+    } else {
+        layout.to_string()
+    }
+}
+
+/// Speaker designation for a channel count, e.g. 6 -> "5.1"
+fn channel_count_designation(channels: u32) -> Option<&'static str> {
+    match channels {
+        1 => Some("1.0"),
+        2 => Some("2.0"),
+        6 => Some("5.1"),
+        8 => Some("7.1"),
+        _ => None,
+    }
+}
+
+/// Bits per sample of a lossless audio stream, 0 for lossy codecs
+fn audio_bit_depth(codec: &str, stream: &serde_json::Value) -> u8 {
+    const LOSSLESS: [&str; 3] = ["alac", "flac", "truehd"];
+    if !codec.starts_with("pcm_") && !LOSSLESS.contains(&codec) {
+        return 0;
+    }
+
+    // ALAC/FLAC report the real depth in bits_per_raw_sample (24-bit is stored as s32)
+    let reported = ["bits_per_raw_sample", "bits_per_sample"]
+        .iter()
+        .filter_map(|key| match &stream[*key] {
+            serde_json::Value::String(bits) => bits.parse::<u8>().ok(),
+            value => value.as_u64().and_then(|bits| u8::try_from(bits).ok()),
         })
+        .find(|bits| *bits > 0);
+    if let Some(bits) = reported {
+        return bits;
+    }
+
+    // pcm_s24le, pcm_f32be, ...
+    let from_name: String = codec
+        .trim_start_matches("pcm_")
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+    from_name.parse().unwrap_or(0)
+}
+
+/// Format an ffprobe H.264 level (e.g. 41) as "4.1"
+pub fn format_h264_level(level: u32) -> String {
+    format!("{}.{}", level / 10, level % 10)
+}
+
+fn format_frame_rates(rates: &[f64]) -> String {
+    rates
+        .iter()
+        .map(|rate| rate.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse a "W:H" (or "W/H") ratio such as an aspect ratio; `None` for zero terms like ffprobe's "0:1"
+pub fn parse_ratio(ratio: &str) -> Option<(u32, u32)> {
+    let (num, den) = ratio.split_once(':').or_else(|| ratio.split_once('/'))?;
+    let num: u32 = num.trim().parse().ok()?;
+    let den: u32 = den.trim().parse().ok()?;
+    if num == 0 || den == 0 {
+        return None;
+    }
+    Some((num, den))
+}
+
+/// Frame size after a clockwise rotation in degrees
+fn rotate_resolution(resolution: Resolution, rotation: u16) -> Resolution {
+    if rotation % 180 == 90 {
+        Resolution::new(resolution.height, resolution.width)
+    } else {
+        resolution
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
 }
 
+/// Relative tolerance when matching aspect ratios, so 1366x768 counts as 16:9
+const ASPECT_RATIO_TOLERANCE: f64 = 0.01;
+
+/// Clockwise rotation (0, 90, 180 or 270 degrees) a player applies when showing
+/// the stream, from the display matrix side data or the legacy `rotate` tag
+fn stream_rotation(video_stream: &serde_json::Value) -> u16 {
+    // The display matrix reports counterclockwise degrees, e.g. -90 for portrait phone footage
+    let display_matrix = video_stream["side_data_list"]
+        .as_array()
+        .and_then(|entries| {
+            entries
+                .iter()
+                .find(|entry| entry["side_data_type"] == "Display Matrix")
+        })
+        .and_then(|entry| entry["rotation"].as_f64())
+        .map(|degrees| -degrees);
+    let rotate_tag = video_stream["tags"]["rotate"]
+        .as_str()
+        .and_then(|degrees| degrees.parse::<f64>().ok());
+
+    let degrees = display_matrix.or(rotate_tag).unwrap_or(0.0);
+    ((degrees / 90.0).round() as i64 * 90).rem_euclid(360) as u16
+}
+
 pub struct ComplianceEngine {
     standards: ContentStandards,
 }
@@ -114,6 +636,11 @@ impl ComplianceEngine {
         Ok(ComplianceEngine { standards })
     }
 
+    /// Create an engine that enforces the given standards instead of the defaults
+    pub fn with_standards(standards: ContentStandards) -> Self {
+        ComplianceEngine { standards }
+    }
+
     #[instrument(skip(self))]
     pub fn analyze_compliance(&self, metadata: &VideoMetadata) -> ComplianceResult {
         info!("Analyzing compliance for video file");
@@ -121,13 +648,15 @@ impl ComplianceEngine {
         let mut violations = Vec::new();
         let mut recommendations = Vec::new();
         let mut score = 100u8;
+        let codec_format = self.standards.codec_format(&metadata.codec);
 
-        // Check video codec compliance
-        if !self
-            .standards
-            .video
-            .preferred_codecs
-            .contains(&metadata.codec)
+        // Check video codec compliance; intermediate codecs are checked against their format below
+        if codec_format.is_none()
+            && !self
+                .standards
+                .video
+                .preferred_codecs
+                .contains(&metadata.codec)
         {
             violations.push(ComplianceViolation {
                 severity: ViolationSeverity::Critical,
@@ -140,52 +669,74 @@ impl ComplianceEngine {
             recommendations.push("Convert to H.264 codec for optimal compatibility".to_string());
         }
 
-        // Check resolution compliance
-        let is_preferred_res = self
-            .standards
-            .video
-            .preferred_resolutions
-            .contains(&metadata.resolution);
-        let is_acceptable_res = self
-            .standards
-            .video
-            .acceptable_resolutions
-            .contains(&metadata.resolution);
+        let (content_type, content_reason) =
+            detect_content_type_with_reason(metadata, Path::new(&metadata.file_name));
+
+        // Check resolution compliance (vertical video only against portrait resolutions)
+        let (preferred_res, acceptable_res) = self.allowed_resolutions(&content_type);
+        let resolution = metadata.effective_resolution();
+        let is_preferred_res = preferred_res.contains(&resolution);
+        let is_acceptable_res = acceptable_res.contains(&resolution);
+        let preferred_res_text = match content_type {
+            ContentType::Vertical => format!(
+                "Preferred: {} (vertical)",
+                format_resolutions(&preferred_res)
+            ),
+            _ => format!("Preferred: {}", format_resolutions(&preferred_res)),
+        };
 
         if !is_preferred_res && !is_acceptable_res {
             violations.push(ComplianceViolation {
                 severity: ViolationSeverity::Critical,
                 category: ViolationCategory::Resolution,
                 description: "Resolution not supported".to_string(),
-                current_value: metadata.resolution.clone(),
-                expected_value: format!(
-                    "Preferred: {}",
-                    self.standards.video.preferred_resolutions.join(", ")
-                ),
+                current_value: resolution.to_string(),
+                expected_value: preferred_res_text.clone(),
             });
             score = score.saturating_sub(25);
-            recommendations
-                .push("Resize to 1920x1080 or 1280x720 for standard content".to_string());
+            recommendations.push(
+                match determine_target_resolution(metadata, &self.standards) {
+                    Some(target) => format!(
+                        "Resize to {} ({})",
+                        target, self.standards.video.scaling_policy
+                    ),
+                    None => "Resize to 1920x1080 or 1280x720 for standard content".to_string(),
+                },
+            );
         } else if !is_preferred_res {
             violations.push(ComplianceViolation {
                 severity: ViolationSeverity::Warning,
                 category: ViolationCategory::Resolution,
                 description: "Resolution acceptable but not preferred".to_string(),
-                current_value: metadata.resolution.clone(),
-                expected_value: format!(
-                    "Preferred: {}",
-                    self.standards.video.preferred_resolutions.join(", ")
-                ),
+                current_value: resolution.to_string(),
+                expected_value: preferred_res_text,
             });
             score = score.saturating_sub(10);
         }
 
         // Check container format
-        if self
+        let container = metadata.container.to_lowercase();
+        if let Some(format) = codec_format {
+            if !format.containers.contains(&container) {
+                violations.push(ComplianceViolation {
+                    severity: ViolationSeverity::Critical,
+                    category: ViolationCategory::Container,
+                    description: format!("Container not supported for {}", format.name),
+                    current_value: metadata.container.clone(),
+                    expected_value: format.containers.join(", "),
+                });
+                score = score.saturating_sub(15);
+                recommendations.push(format!(
+                    "Rewrap {} in {}",
+                    format.name,
+                    format.containers.join(" or ").to_uppercase()
+                ));
+            }
+        } else if self
             .standards
             .video
             .unsupported_containers
-            .contains(&metadata.container.to_lowercase())
+            .contains(&container)
         {
             violations.push(ComplianceViolation {
                 severity: ViolationSeverity::Critical,
@@ -196,19 +747,26 @@ impl ComplianceEngine {
             });
             score = score.saturating_sub(15);
             recommendations.push("Convert to MP4 or MOV container".to_string());
+        } else if !self.standards.video.containers.contains(&container) {
+            violations.push(ComplianceViolation {
+                severity: ViolationSeverity::Warning,
+                category: ViolationCategory::Container,
+                description: "Container not in preferred list".to_string(),
+                current_value: metadata.container.clone(),
+                expected_value: self.standards.video.containers.join(", "),
+            });
+            score = score.saturating_sub(10);
+            recommendations.push("Remux to MP4 or MOV (stream copy)".to_string());
         }
 
-        // Check audio codec
-        if !self
-            .standards
-            .audio
-            .preferred_codecs
-            .contains(&metadata.audio_codec)
+        // Check audio codec (pcm_s16le, pcm_s24le, ... all count as "pcm")
+        let audio_codec = audio_codec_family(&metadata.audio_codec).to_string();
+        if !self.standards.audio.preferred_codecs.contains(&audio_codec)
             && !self
                 .standards
                 .audio
                 .acceptable_codecs
-                .contains(&metadata.audio_codec)
+                .contains(&audio_codec)
         {
             violations.push(ComplianceViolation {
                 severity: ViolationSeverity::Warning,
@@ -227,7 +785,7 @@ impl ComplianceEngine {
             .standards
             .audio
             .acceptable_codecs
-            .contains(&metadata.audio_codec)
+            .contains(&audio_codec)
         {
             violations.push(ComplianceViolation {
                 severity: ViolationSeverity::Info,
@@ -242,42 +800,43 @@ impl ComplianceEngine {
             score = score.saturating_sub(5);
         }
 
-        // Check HDR restrictions and unsupported color spaces
-        let mut hdr_violation = false;
+        // Check HDR restrictions, classified from transfer characteristics and side data
+        let hdr_format = metadata.hdr_format();
+        let hdr_violation = hdr_format.standards_key().is_some_and(|key| {
+            self.standards
+                .quality
+                .hdr_restrictions
+                .iter()
+                .any(|restricted| restricted.eq_ignore_ascii_case(key))
+        });
 
-        for restricted in &self.standards.quality.hdr_restrictions {
-            if metadata
-                .color_space
-                .to_lowercase()
-                .contains(&restricted.to_lowercase())
-            {
-                violations.push(ComplianceViolation {
-                    severity: ViolationSeverity::Critical,
-                    category: ViolationCategory::HDR,
-                    description: "HDR content not supported by delivery pipeline".to_string(),
-                    current_value: metadata.color_space.clone(),
-                    expected_value: "Rec. 709 (SDR)".to_string(),
-                });
-                score = score.saturating_sub(30);
-                recommendations.push("Convert to SDR (Rec. 709) color space".to_string());
-                hdr_violation = true;
-                break;
-            }
+        if hdr_violation {
+            violations.push(ComplianceViolation {
+                severity: ViolationSeverity::Critical,
+                category: ViolationCategory::HDR,
+                description: format!("{} content not supported by delivery pipeline", hdr_format),
+                current_value: metadata.hdr_summary(),
+                expected_value: "Rec. 709 (SDR)".to_string(),
+            });
+            score = score.saturating_sub(30);
+            recommendations.push(format!(
+                "Tone map to SDR (Rec. 709) using the {} operator",
+                self.standards.quality.tone_mapping
+            ));
         }
 
-        // Also check unsupported color spaces (like bt2020)
+        // Also check wide-gamut primaries (BT.2020, P3) on SDR video
         if !hdr_violation {
-            for unsupported in &self.standards.quality.unsupported_color_spaces {
-                if metadata
-                    .color_space
-                    .to_lowercase()
-                    .contains(&unsupported.to_lowercase())
-                {
+            for unsupported in &self.standards.quality.unsupported_color_primaries {
+                if metadata.color_primaries.eq_ignore_ascii_case(unsupported) {
                     violations.push(ComplianceViolation {
                         severity: ViolationSeverity::Critical,
                         category: ViolationCategory::HDR,
                         description: "Color space not supported by delivery pipeline".to_string(),
-                        current_value: metadata.color_space.clone(),
+                        current_value: format!(
+                            "{} (primaries {})",
+                            metadata.color_space, metadata.color_primaries
+                        ),
                         expected_value: "Rec. 709 (SDR)".to_string(),
                     });
                     score = score.saturating_sub(25);
@@ -287,44 +846,718 @@ impl ComplianceEngine {
             }
         }
 
-        let is_compliant = violations
-            .iter()
-            .all(|v| matches!(v.severity, ViolationSeverity::Info));
+        // Untagged SDR video is assumed to be Rec. 709; tagging it needs no re-encode
+        let untagged = metadata.color_space.is_empty() || metadata.color_space == "unknown";
+        if untagged
+            && !hdr_format.is_hdr()
+            && !violations
+                .iter()
+                .any(|v| v.category == ViolationCategory::HDR)
+        {
+            violations.push(ComplianceViolation {
+                severity: ViolationSeverity::Info,
+                category: ViolationCategory::ColorSpace,
+                description: "Color space not tagged".to_string(),
+                current_value: "unknown".to_string(),
+                expected_value: self.standards.quality.color_spaces.join(", "),
+            });
+            recommendations.push("Tag the video as Rec. 709 (bt709)".to_string());
+        }
 
-        ComplianceResult {
-            is_compliant,
+        let mut result = ComplianceResult {
+            is_compliant: false,
             score,
+            hdr_format,
             violations,
             recommendations,
-        }
+        };
+
+        self.check_vertical(&content_type, &mut result);
+        self.check_frame_rate(metadata, &content_type, &mut result);
+        self.check_bitrate(metadata, &content_type, &content_reason, &mut result);
+        self.check_profile(metadata, &mut result);
+        self.check_keyframe_interval(metadata, &mut result);
+        self.check_aspect_ratio(metadata, &mut result);
+        self.check_rotation(metadata, &mut result);
+        self.check_faststart(metadata, &mut result);
+        self.check_interlacing(metadata, &mut result);
+        self.check_pixel_format(metadata, &mut result);
+        self.check_audio_format(metadata, &mut result);
+        self.check_audio_bitrate(metadata, &mut result);
+
+        result.is_compliant = result
+            .violations
+            .iter()
+            .all(|v| matches!(v.severity, ViolationSeverity::Info));
+
+        result
     }
 
-    pub fn get_standards(&self) -> &ContentStandards {
-        &self.standards
+    /// Preferred and acceptable resolutions for the content type; vertical
+    /// video is only ever compared with portrait resolutions
+    fn allowed_resolutions(
+        &self,
+        content_type: &ContentType,
+    ) -> (Vec<Resolution>, Vec<Resolution>) {
+        let video = &self.standards.video;
+        let filter = |list: &[Resolution]| -> Vec<Resolution> {
+            list.iter()
+                .copied()
+                .filter(|res| {
+                    *content_type != ContentType::Vertical
+                        || res.orientation() == Orientation::Portrait
+                })
+                .collect()
+        };
+        (
+            filter(&video.preferred_resolutions),
+            filter(&video.acceptable_resolutions),
+        )
     }
-}
 
-impl ComplianceResult {
-    pub fn display(&self) {
-        println!("\n{}", "📋 Compliance Analysis".bright_blue().bold());
-        println!("{}", "=".repeat(60).bright_blue());
+    /// Vertical delivery guidance that cannot be measured from the stream
+    fn check_vertical(&self, content_type: &ContentType, result: &mut ComplianceResult) {
+        if *content_type == ContentType::Vertical {
+            result.recommendations.push(
+                "Keep the lower third of vertical video clear for platform-provided captions"
+                    .to_string(),
+            );
+        }
+    }
 
-        let status_color = if self.is_compliant { "green" } else { "red" };
-        let status_icon = if self.is_compliant { "✅" } else { "❌" };
-        let status_text = if self.is_compliant {
-            "COMPLIANT"
+    /// Check the frame rate against the rates allowed for the content type
+    fn check_frame_rate(
+        &self,
+        metadata: &VideoMetadata,
+        content_type: &ContentType,
+        result: &mut ComplianceResult,
+    ) {
+        if metadata.fps <= 0.0 {
+            return; // Frame rate unknown
+        }
+
+        let video = &self.standards.video;
+        if let Some(timing) = metadata.frame_timing.as_ref().filter(|t| t.variable) {
+            let allowed = video.frame_rates_for(content_type);
+            let target = nearest_frame_rate(timing.average_fps, &allowed);
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::FrameRate,
+                    description: "Variable frame rate".to_string(),
+                    current_value: timing.summary(),
+                    expected_value: format!(
+                        "Constant frame rate ({})",
+                        format_frame_rates(&allowed)
+                    ),
+                },
+                10,
+                target.map(|rate| format!("Conform to a constant {} fps", rate)),
+            );
+        }
+
+        let allowed = video.frame_rates_for(content_type);
+        // Narrower than the preferred list only through a content_frame_rates entry
+        let content_specific = allowed != video.preferred_frame_rates;
+
+        if allowed
+            .iter()
+            .any(|rate| frame_rate_matches(metadata.fps, *rate))
+        {
+            return;
+        }
+
+        let allowed_list = format_frame_rates(&allowed);
+        let expected_value = if content_specific {
+            format!("{} ({:?})", allowed_list, content_type)
         } else {
-            "NON-COMPLIANT"
+            allowed_list.clone()
         };
 
-        println!(
-            "{} Status: {}",
-            status_icon,
-            status_text.color(status_color).bold()
-        );
-        println!("📊 Compliance Score: {}/100", self.score.to_string().bold());
-
-        if !self.violations.is_empty() {
+        if content_specific
+            && video
+                .preferred_frame_rates
+                .iter()
+                .any(|rate| frame_rate_matches(metadata.fps, *rate))
+        {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::FrameRate,
+                    description: format!("Frame rate not preferred for {:?} content", content_type),
+                    current_value: format!("{:.3} fps", metadata.fps),
+                    expected_value,
+                },
+                5,
+                None,
+            );
+        } else {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::FrameRate,
+                    description: "Frame rate not in preferred list".to_string(),
+                    current_value: format!("{:.3} fps", metadata.fps),
+                    expected_value,
+                },
+                10,
+                // VFR footage is conformed from its average rate instead
+                conversion_frame_rate(metadata.fps, &allowed)
+                    .filter(|_| !metadata.is_variable_frame_rate())
+                    .map(|rate| {
+                        let method = if is_integer_ratio(metadata.fps, rate) {
+                            "frame dropping".to_string()
+                        } else {
+                            video.frame_rate_conversion.to_string()
+                        };
+                        format!("Convert to {} fps ({})", rate, method)
+                    }),
+            );
+        }
+    }
+
+    /// Check the video stream bitrate against the range for the content type
+    fn check_bitrate(
+        &self,
+        metadata: &VideoMetadata,
+        content_type: &ContentType,
+        content_reason: &str,
+        result: &mut ComplianceResult,
+    ) {
+        // Intermediate codecs are delivered at their native data rate
+        if self.standards.codec_format(&metadata.codec).is_some() {
+            return;
+        }
+        let Some(range) = content_type
+            .bitrate_key()
+            .and_then(|key| self.standards.video.bitrate_ranges.get(key))
+        else {
+            return;
+        };
+        let Some(bitrate) = metadata.effective_video_bitrate() else {
+            return; // Bitrate unknown
+        };
+
+        let kbps = bitrate / 1000;
+        let range_text = format!(
+            "{}–{} kbps ({} range, {:?} content: {})",
+            range.min_kbps, range.max_kbps, range.content_type, content_type, content_reason
+        );
+
+        if kbps < range.min_kbps as u64 {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::Bitrate,
+                    description: format!("Video bitrate below the {} range", range.content_type),
+                    current_value: format!("{} kbps", kbps),
+                    expected_value: range_text,
+                },
+                10,
+                Some(format!(
+                    "Re-encode video at {}–{} kbps for {} content",
+                    range.min_kbps, range.max_kbps, range.content_type
+                )),
+            );
+        } else if kbps > range.max_kbps as u64 {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::Bitrate,
+                    description: format!("Video bitrate above the {} range", range.content_type),
+                    current_value: format!("{} kbps", kbps),
+                    expected_value: range_text,
+                },
+                5,
+                Some(format!(
+                    "Re-encode video at {}–{} kbps for {} content",
+                    range.min_kbps, range.max_kbps, range.content_type
+                )),
+            );
+        }
+    }
+
+    /// Check the codec profile and, for H.264, the level
+    fn check_profile(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        let video = &self.standards.video;
+        let profile = metadata.profile.as_str();
+        if profile.is_empty() || profile == "unknown" {
+            return;
+        }
+        let profiles = match self.standards.codec_format(&metadata.codec) {
+            Some(format) if format.profiles.is_empty() => return,
+            Some(format) => &format.profiles,
+            None => &video.profiles,
+        };
+
+        if !profiles.iter().any(|p| p == profile) {
+            // High 10, High 4:2:2, High 4:4:4 etc. need decoders most players lack
+            let extended_h264 = metadata.codec == "h264" && profile.starts_with("high ");
+            let (severity, deduction) = if extended_h264 {
+                (ViolationSeverity::Critical, 20)
+            } else {
+                (ViolationSeverity::Warning, 10)
+            };
+
+            result.add_violation(
+                ComplianceViolation {
+                    severity,
+                    category: ViolationCategory::Profile,
+                    description: "Codec profile not supported".to_string(),
+                    current_value: metadata.profile.clone(),
+                    expected_value: profiles.join(", "),
+                },
+                deduction,
+                Some(format!(
+                    "Re-encode using the {} profile{}",
+                    profiles.join(" or "),
+                    self.standards
+                        .codec_format(&metadata.codec)
+                        .map_or("", |format| manual_fix_note(metadata, format))
+                )),
+            );
+            return;
+        }
+
+        if metadata.codec != "h264" || metadata.level == 0 {
+            return;
+        }
+        if let Some(max_level) = video.max_levels.get(profile) {
+            let level = metadata.level as f64 / 10.0;
+            if level > *max_level + f64::EPSILON {
+                result.add_violation(
+                    ComplianceViolation {
+                        severity: ViolationSeverity::Warning,
+                        category: ViolationCategory::Profile,
+                        description: format!("H.264 level too high for the {} profile", profile),
+                        current_value: format_h264_level(metadata.level),
+                        expected_value: format!("≤ {}", max_level),
+                    },
+                    5,
+                    Some(format!("Re-encode at H.264 level {} or lower", max_level)),
+                );
+            }
+        }
+    }
+
+    /// Check the keyframe interval against `keyframe_interval_min`. Scene-change
+    /// keyframes shorten individual GOPs, so the typical (most common) GOP length
+    /// is taken as the encoder's configured interval.
+    fn check_keyframe_interval(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        // ProRes and DNxHD are intra-frame: every frame is a keyframe
+        if self.standards.codec_format(&metadata.codec).is_some() {
+            return;
+        }
+        let Some(gop) = &metadata.gop else {
+            return;
+        };
+        if gop.keyframe_count < 2 {
+            return; // A single keyframe cannot be too frequent
+        }
+
+        let min_interval = self.standards.quality.keyframe_interval_min as f64;
+        // Allow for timestamp rounding, e.g. 59 frames at 29.97 fps
+        if gop.typical_gop_seconds + 0.05 < min_interval {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::Keyframe,
+                    description: "Keyframe interval too short".to_string(),
+                    current_value: gop.summary(),
+                    expected_value: format!("≥ {} s", min_interval),
+                },
+                10,
+                Some(format!(
+                    "Re-encode with a keyframe interval of at least {} seconds",
+                    min_interval
+                )),
+            );
+        }
+    }
+
+    /// Check audio sample rate, lossless bit depth, channel count and layout
+    fn check_audio_format(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        if metadata.audio_codec == "none" {
+            return;
+        }
+        let audio = &self.standards.audio;
+
+        if metadata.audio_sample_rate > 0
+            && !audio.sample_rates.contains(&metadata.audio_sample_rate)
+        {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::Audio,
+                    description: "Audio sample rate not supported".to_string(),
+                    current_value: format!("{} Hz", metadata.audio_sample_rate),
+                    expected_value: audio
+                        .sample_rates
+                        .iter()
+                        .map(|rate| format!("{} Hz", rate))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                },
+                10,
+                Some("Resample audio to 48 kHz".to_string()),
+            );
+        }
+
+        // Bit depth only applies to lossless audio; lossy codecs report none
+        if metadata.audio_bit_depth > 0 && !audio.bit_depths.contains(&metadata.audio_bit_depth) {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::Audio,
+                    description: "Audio bit depth not supported".to_string(),
+                    current_value: format!(
+                        "{}-bit ({})",
+                        metadata.audio_bit_depth, metadata.audio_codec
+                    ),
+                    expected_value: audio
+                        .bit_depths
+                        .iter()
+                        .map(|depth| format!("{}-bit", depth))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                },
+                10,
+                Some("Convert audio to 24-bit PCM".to_string()),
+            );
+        }
+
+        if metadata.audio_channels > 0 {
+            let layout =
+                channel_layout_name(metadata.audio_channels, &metadata.audio_channel_layout);
+            let designation = channel_count_designation(metadata.audio_channels);
+            // A 2-channel stream is only "2.0" when it is plain stereo L/R, not e.g. a downmix
+            let matches_designation = designation
+                .is_some_and(|d| audio.channels.iter().any(|c| c == d))
+                && (metadata.audio_channels != 2 || layout == "stereo" || layout == "2.0");
+
+            if !audio.channels.contains(&layout) && !matches_designation {
+                result.add_violation(
+                    ComplianceViolation {
+                        severity: ViolationSeverity::Warning,
+                        category: ViolationCategory::Audio,
+                        description: "Audio channel layout not supported".to_string(),
+                        current_value: format!(
+                            "{} ({} channel{})",
+                            layout,
+                            metadata.audio_channels,
+                            if metadata.audio_channels == 1 {
+                                ""
+                            } else {
+                                "s"
+                            }
+                        ),
+                        expected_value: audio.channels.join(", "),
+                    },
+                    10,
+                    Some("Mix audio down to stereo (L/R)".to_string()),
+                );
+            }
+        }
+    }
+
+    /// Check lossy audio against `audio.bitrate_ranges` and require constant bitrate
+    fn check_audio_bitrate(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        let Some(&required_kbps) = self
+            .standards
+            .audio
+            .bitrate_ranges
+            .get(&metadata.audio_codec)
+        else {
+            return;
+        };
+
+        // Prefer the stream's declared bitrate, else the measured one
+        let measured_kbps = if metadata.audio_bitrate > 0 {
+            Some(metadata.audio_bitrate as f64 / 1000.0)
+        } else {
+            metadata.audio_packets.as_ref().map(|a| a.mean_kbps)
+        };
+
+        // Allow for container overhead and rounding, e.g. 317 kbps reported for 320k
+        if let Some(kbps) = measured_kbps.filter(|kbps| *kbps < required_kbps as f64 * 0.97) {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::Audio,
+                    description: format!(
+                        "{} bitrate below required",
+                        metadata.audio_codec.to_uppercase()
+                    ),
+                    current_value: format!("{:.0} kbps", kbps),
+                    expected_value: format!("{} kbps CBR", required_kbps),
+                },
+                10,
+                Some(format!(
+                    "Re-encode {} audio at {} kbps CBR or use PCM",
+                    metadata.audio_codec.to_uppercase(),
+                    required_kbps
+                )),
+            );
+        }
+
+        if let Some(analysis) = metadata
+            .audio_packets
+            .as_ref()
+            .filter(|a| !a.constant_bitrate)
+        {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::Audio,
+                    description: format!(
+                        "{} audio is variable bitrate",
+                        metadata.audio_codec.to_uppercase()
+                    ),
+                    current_value: analysis.summary(),
+                    expected_value: format!("{} kbps CBR", required_kbps),
+                },
+                10,
+                Some(format!(
+                    "Re-encode {} audio at {} kbps CBR or use PCM",
+                    metadata.audio_codec.to_uppercase(),
+                    required_kbps
+                )),
+            );
+        }
+    }
+
+    /// Check pixel and display aspect ratios, catching anamorphic files whose
+    /// pixel dimensions look standard
+    fn check_aspect_ratio(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        let Some(display_aspect) = metadata.display_aspect() else {
+            return;
+        };
+
+        if !metadata.has_square_pixels() {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::AspectRatio,
+                    description: "Non-square pixels (anamorphic video)".to_string(),
+                    current_value: format!(
+                        "SAR {}, DAR {} ({})",
+                        metadata.sample_aspect_ratio,
+                        metadata.display_aspect_ratio_name(),
+                        metadata.effective_resolution()
+                    ),
+                    expected_value: "SAR 1:1".to_string(),
+                },
+                10,
+                Some("Re-encode with square pixels (SAR 1:1)".to_string()),
+            );
+        }
+
+        let allowed = &self.standards.video.aspect_ratios;
+        let matches_allowed = allowed.iter().filter_map(|r| parse_ratio(r)).any(|(w, h)| {
+            let ratio = w as f64 / h as f64;
+            (display_aspect - ratio).abs() / ratio <= ASPECT_RATIO_TOLERANCE
+        });
+        if !matches_allowed {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::AspectRatio,
+                    description: "Display aspect ratio not supported".to_string(),
+                    current_value: format!(
+                        "{} ({:.3})",
+                        metadata.display_aspect_ratio_name(),
+                        display_aspect
+                    ),
+                    expected_value: allowed.join(", "),
+                },
+                10,
+                Some(format!(
+                    "Pad or crop to an allowed aspect ratio ({})",
+                    allowed.join(", ")
+                )),
+            );
+        }
+    }
+
+    /// Flag MP4/MOV files whose index follows the media data, so playback
+    /// cannot start until the whole file has downloaded
+    fn check_faststart(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        if metadata.faststart != Some(false) {
+            return;
+        }
+        result.add_violation(
+            ComplianceViolation {
+                severity: ViolationSeverity::Warning,
+                category: ViolationCategory::Faststart,
+                description: "Index (moov) stored after media data".to_string(),
+                current_value: "moov after mdat".to_string(),
+                expected_value: "moov before mdat (faststart)".to_string(),
+            },
+            5,
+            Some("Remux with -movflags +faststart (stream copy)".to_string()),
+        );
+    }
+
+    /// Flag rotation stored as metadata, which some players and editors ignore
+    fn check_rotation(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        if metadata.rotation == 0 {
+            return;
+        }
+
+        result.add_violation(
+            ComplianceViolation {
+                severity: ViolationSeverity::Warning,
+                category: ViolationCategory::Rotation,
+                description: "Rotation stored as display metadata".to_string(),
+                current_value: format!(
+                    "{}° (stored {}, displayed {})",
+                    metadata.rotation,
+                    metadata.resolution,
+                    metadata.effective_resolution()
+                ),
+                expected_value: "0° (upright pixels)".to_string(),
+            },
+            5,
+            Some("Re-encode with the rotation applied to the pixels (transpose)".to_string()),
+        );
+    }
+
+    /// Check for interlaced video, preferring the idet verdict over the signalled field order
+    fn check_interlacing(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        if !self.standards.quality.require_progressive || !metadata.is_interlaced() {
+            return;
+        }
+
+        let mut current_value = metadata.interlaced_field_order().to_string();
+        if let Some(interlace) = &metadata.interlace {
+            current_value.push_str(&format!(" ({})", interlace.summary()));
+        } else {
+            current_value.push_str(" (signalled field order)");
+        }
+
+        result.add_violation(
+            ComplianceViolation {
+                severity: ViolationSeverity::Warning,
+                category: ViolationCategory::Interlacing,
+                description: "Interlaced video".to_string(),
+                current_value,
+                expected_value: "progressive".to_string(),
+            },
+            10,
+            Some("Deinterlace to progressive frames (bwdif)".to_string()),
+        );
+    }
+
+    /// Check chroma subsampling and bit depth derived from the pixel format,
+    /// using the codec's own format rules for intermediate codecs
+    fn check_pixel_format(&self, metadata: &VideoMetadata, result: &mut ComplianceResult) {
+        let quality = &self.standards.quality;
+        let codec_format = self.standards.codec_format(&metadata.codec);
+        let (chroma_subsampling, bit_depths) = match codec_format {
+            Some(format) => (&format.chroma_subsampling, &format.bit_depths),
+            None => (&quality.chroma_subsampling, &quality.bit_depths),
+        };
+
+        if !metadata.chroma_subsampling.is_empty()
+            && !chroma_subsampling.contains(&metadata.chroma_subsampling)
+        {
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::ChromaSubsampling,
+                    description: "Chroma subsampling not supported".to_string(),
+                    current_value: format!(
+                        "{} ({})",
+                        metadata.chroma_subsampling, metadata.pix_fmt
+                    ),
+                    expected_value: chroma_subsampling.join(", "),
+                },
+                10,
+                Some(match codec_format {
+                    Some(format) => format!(
+                        "Re-encode {} with {} chroma subsampling{}",
+                        format.name,
+                        chroma_subsampling.join(" or "),
+                        manual_fix_note(metadata, format)
+                    ),
+                    None => format!(
+                        "Re-encode with {} chroma subsampling (e.g. yuv420p)",
+                        chroma_subsampling.join(" or ")
+                    ),
+                }),
+            );
+        }
+
+        if metadata.bit_depth > 0 && !bit_depths.contains(&metadata.bit_depth) {
+            let depths = bit_depths
+                .iter()
+                .map(|depth| format!("{}-bit", depth))
+                .collect::<Vec<_>>();
+            result.add_violation(
+                ComplianceViolation {
+                    severity: ViolationSeverity::Warning,
+                    category: ViolationCategory::BitDepth,
+                    description: "Video bit depth not supported".to_string(),
+                    current_value: format!("{}-bit ({})", metadata.bit_depth, metadata.pix_fmt),
+                    expected_value: depths.join(", "),
+                },
+                10,
+                Some(match codec_format {
+                    Some(format) => format!(
+                        "Re-encode {} at {}{}",
+                        format.name,
+                        depths.join(" or "),
+                        manual_fix_note(metadata, format)
+                    ),
+                    None => "Re-encode to 8-bit video".to_string(),
+                }),
+            );
+        }
+    }
+
+    pub fn get_standards(&self) -> &ContentStandards {
+        &self.standards
+    }
+}
+
+impl ComplianceResult {
+    /// Record a violation, deduct from the score and add an optional recommendation
+    fn add_violation(
+        &mut self,
+        violation: ComplianceViolation,
+        deduction: u8,
+        recommendation: Option<String>,
+    ) {
+        self.violations.push(violation);
+        self.score = self.score.saturating_sub(deduction);
+        if let Some(recommendation) = recommendation {
+            if !self.recommendations.contains(&recommendation) {
+                self.recommendations.push(recommendation);
+            }
+        }
+    }
+
+    pub fn display(&self) {
+        println!("\n{}", "📋 Compliance Analysis".bright_blue().bold());
+        println!("{}", "=".repeat(60).bright_blue());
+
+        let status_color = if self.is_compliant { "green" } else { "red" };
+        let status_icon = if self.is_compliant { "✅" } else { "❌" };
+        let status_text = if self.is_compliant {
+            "COMPLIANT"
+        } else {
+            "NON-COMPLIANT"
+        };
+
+        println!(
+            "{} Status: {}",
+            status_icon,
+            status_text.color(status_color).bold()
+        );
+        println!("📊 Compliance Score: {}/100", self.score.to_string().bold());
+        println!("🌈 Dynamic Range: {}", self.hdr_format.to_string().bold());
+
+        if !self.violations.is_empty() {
             println!("\n{}", "⚠️  Violations Found:".yellow().bold());
             for (i, violation) in self.violations.iter().enumerate() {
                 let severity_icon = match violation.severity {
@@ -395,61 +1628,266 @@ pub struct ProcessingSummary {
     pub resolutions: HashMap<String, usize>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct VideoMetadata {
+    /// Source file name, used for content type hints
+    pub file_name: String,
     pub codec: String,
-    pub resolution: String,
+    pub resolution: Resolution,
+    /// Sample (pixel) aspect ratio as reported by ffprobe, e.g. "1:1"; "0:1" or empty when unknown
+    pub sample_aspect_ratio: String,
+    /// Display aspect ratio as reported by ffprobe, e.g. "16:9"
+    pub display_aspect_ratio: String,
+    /// Clockwise rotation in degrees (0, 90, 180, 270) applied by players from
+    /// the display matrix; `resolution` is the stored, unrotated frame size
+    pub rotation: u16,
     pub duration: f64,
+    /// Container (format) bitrate in bits per second
     pub bitrate: u64,
+    /// Video stream bitrate in bits per second, 0 when the container does not report it
+    pub video_bitrate: u64,
     pub size: u64,
     pub fps: f64,
     pub audio_codec: String,
     pub audio_sample_rate: u32,
     pub audio_bitrate: u64,
+    /// Audio sample format as reported by ffprobe, e.g. "s16", "s32" or "fltp"
+    pub audio_sample_fmt: String,
+    /// Bits per sample of lossless audio (PCM, ALAC, FLAC), 0 for lossy or unknown
+    pub audio_bit_depth: u8,
+    pub audio_channels: u32,
+    /// Channel layout as reported by ffprobe, e.g. "stereo", "mono" or "5.1(side)"
+    pub audio_channel_layout: String,
+    /// Measured audio bitrate and CBR/VBR classification, scanned for AAC only
+    pub audio_packets: Option<AudioBitrateAnalysis>,
     pub container: String,
     pub profile: String,
+    /// H.264 level as reported by ffprobe (41 = level 4.1), 0 when unknown
+    pub level: u32,
     pub color_space: String,
+    /// Transfer characteristics, e.g. "bt709", "smpte2084" (PQ) or "arib-std-b67" (HLG)
+    pub color_transfer: String,
+    /// Color primaries, e.g. "bt709" or "bt2020"
+    pub color_primaries: String,
+    /// Mastering display, light level and Dolby Vision side data
+    pub hdr: HdrMetadata,
+    /// Pixel format as reported by ffprobe, e.g. "yuv420p10le"
+    pub pix_fmt: String,
+    /// Chroma subsampling derived from the pixel format, e.g. "4:2:0"; empty when unknown
+    pub chroma_subsampling: String,
+    /// Bits per component, 0 when unknown
+    pub bit_depth: u8,
+    /// Keyframe spacing, when the packet scan succeeded
+    pub gop: Option<GopAnalysis>,
+    /// Nominal vs measured frame timing, for variable frame rate detection
+    pub frame_timing: Option<FrameRateAnalysis>,
+    /// Field order signalled in the stream
+    pub field_order: FieldOrder,
+    /// Frame classification from an optional `idet` pass
+    pub interlace: Option<InterlaceAnalysis>,
+    /// Whether the MP4/MOV `moov` index precedes the media data; `None` for
+    /// other containers or when the atom layout could not be read
+    pub faststart: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ContentStandards {
     pub video: VideoStandards,
     pub audio: AudioStandards,
     pub quality: QualityStandards,
+    /// Intermediate codecs (keyed by ffprobe codec name) accepted alongside
+    /// the preferred codecs and checked against their own format rules
+    #[serde(default = "default_codec_formats")]
+    pub codec_formats: HashMap<String, CodecFormat>,
+}
+
+/// Delivery rules for an intermediate codec such as ProRes or DNxHD. These
+/// replace the container, profile, chroma and bit depth rules written for
+/// H.264, so a 10-bit ProRes master is not held to the 8-bit limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CodecFormat {
+    /// Name used in reports, e.g. "Apple ProRes 422"
+    pub name: String,
+    pub containers: Vec<String>,
+    /// Allowed profiles as reported by ffprobe, lowercased; empty allows any
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    pub chroma_subsampling: Vec<String>,
+    pub bit_depths: Vec<u8>,
+}
+
+fn default_codec_formats() -> HashMap<String, CodecFormat> {
+    HashMap::from([
+        (
+            "prores".to_string(),
+            CodecFormat {
+                name: "Apple ProRes 422".to_string(),
+                containers: vec!["mov".to_string()],
+                profiles: vec!["standard".to_string(), "hq".to_string()],
+                chroma_subsampling: vec!["4:2:2".to_string()],
+                bit_depths: vec![10],
+            },
+        ),
+        (
+            // ffprobe reports DNxHR as dnxhd too, with a "dnxhr sq" style profile
+            "dnxhd".to_string(),
+            CodecFormat {
+                name: "Avid DNxHD SQ".to_string(),
+                containers: vec!["mxf".to_string(), "mov".to_string()],
+                profiles: vec!["dnxhd".to_string(), "dnxhr sq".to_string()],
+                chroma_subsampling: vec!["4:2:2".to_string()],
+                bit_depths: vec![8],
+            },
+        ),
+    ])
+}
+
+/// How frame rates without a whole-number ratio to the target are converted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameRateConversion {
+    /// Drop or repeat frames with the `fps` filter
+    #[default]
+    Fps,
+    /// Synthesize in-between frames with motion-compensated `minterpolate` (slow)
+    Interpolate,
+}
+
+impl std::fmt::Display for FrameRateConversion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FrameRateConversion::Fps => "fps filter",
+            FrameRateConversion::Interpolate => "motion interpolation",
+        };
+        f.write_str(name)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct VideoStandards {
-    pub preferred_resolutions: Vec<String>,
-    pub acceptable_resolutions: Vec<String>,
+    pub preferred_resolutions: Vec<Resolution>,
+    pub acceptable_resolutions: Vec<Resolution>,
+    /// How sources with a different aspect ratio are fitted into the target resolution
+    #[serde(default)]
+    pub scaling_policy: ScalingPolicy,
     pub preferred_codecs: Vec<String>,
     pub preferred_frame_rates: Vec<f64>,
+    /// Method for frame rate conversions that cannot just drop frames
+    #[serde(default)]
+    pub frame_rate_conversion: FrameRateConversion,
+    /// Allowed frame rates per content type (`screen_capture`, `live_action`, `vertical`)
+    #[serde(default)]
+    pub content_frame_rates: HashMap<String, Vec<f64>>,
     pub bitrate_ranges: HashMap<String, BitRateRange>,
     pub containers: Vec<String>,
     pub unsupported_containers: Vec<String>,
     pub profiles: Vec<String>,
+    /// Highest H.264 level allowed for each profile, e.g. `high = 5.1`
+    #[serde(default)]
+    pub max_levels: HashMap<String, f64>,
+    /// Allowed display aspect ratios as "W:H"
+    #[serde(default = "default_aspect_ratios")]
+    pub aspect_ratios: Vec<String>,
+}
+
+impl VideoStandards {
+    /// Frame rates allowed for the content type: its `content_frame_rates` entry
+    /// narrowed to `preferred_frame_rates`, so a profile overriding only the
+    /// preferred list still restricts every content type. Falls back to
+    /// `preferred_frame_rates` when there is no entry or nothing is left of it.
+    pub fn frame_rates_for(&self, content_type: &ContentType) -> Vec<f64> {
+        let preferred = &self.preferred_frame_rates;
+        content_type
+            .standards_key()
+            .and_then(|key| self.content_frame_rates.get(key))
+            .map(|rates| {
+                rates
+                    .iter()
+                    .copied()
+                    .filter(|rate| preferred.iter().any(|p| frame_rate_matches(*rate, *p)))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|rates| !rates.is_empty())
+            .unwrap_or_else(|| preferred.clone())
+    }
+}
+
+fn default_aspect_ratios() -> Vec<String> {
+    vec!["16:9".to_string(), "16:10".to_string(), "9:16".to_string()]
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct AudioStandards {
     pub preferred_codecs: Vec<String>,
     pub acceptable_codecs: Vec<String>,
+    /// Codec written when audio has to be re-encoded
+    #[serde(default)]
+    pub target_codec: AudioTarget,
     pub sample_rates: Vec<u32>,
     pub bit_depths: Vec<u8>,
     pub bitrate_ranges: HashMap<String, u32>,
     pub channels: Vec<String>,
 }
 
+/// Audio codec the fixers encode to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioTarget {
+    /// Uncompressed PCM; MP4 outputs get ALAC instead, since MP4 players rarely handle PCM
+    #[default]
+    Pcm,
+    /// Apple Lossless, valid in both MOV and MP4
+    Alac,
+    /// AAC at the constant bitrate in `audio.bitrate_ranges.aac`, as a last resort
+    Aac,
+}
+
+impl AudioTarget {
+    /// Codec name as compared with `audio.preferred_codecs`
+    pub fn codec_family(&self) -> &'static str {
+        match self {
+            AudioTarget::Pcm => "pcm",
+            AudioTarget::Alac => "alac",
+            AudioTarget::Aac => "aac",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct QualityStandards {
     pub color_spaces: Vec<String>,
-    pub unsupported_color_spaces: Vec<String>,
+    /// Color primaries, as named by ffprobe (`bt2020`, `smpte432`), converted to BT.709
+    #[serde(alias = "unsupported_color_spaces")]
+    pub unsupported_color_primaries: Vec<String>,
     pub keyframe_interval_min: u32,
     pub chroma_subsampling: Vec<String>,
+    /// Allowed video bit depths; 10 and 12-bit are HDR-restricted by default
+    #[serde(default = "default_video_bit_depths")]
+    pub bit_depths: Vec<u8>,
     pub hdr_restrictions: Vec<String>,
+    /// Curve used when HDR video is tone mapped to SDR
+    #[serde(default)]
+    pub tone_mapping: ToneMapOperator,
+    /// Reject interlaced video (the spec and DNxHD delivery are progressive only)
+    #[serde(default = "default_require_progressive")]
+    pub require_progressive: bool,
+}
+
+fn default_video_bit_depths() -> Vec<u8> {
+    vec![8]
+}
+
+fn default_require_progressive() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct BitRateRange {
     pub min_kbps: u32,
     pub max_kbps: u32,
@@ -460,6 +1898,8 @@ pub struct BitRateRange {
 pub struct ComplianceResult {
     pub is_compliant: bool,
     pub score: u8,
+    /// Dynamic range classification of the analyzed stream
+    pub hdr_format: HdrFormat,
     pub violations: Vec<ComplianceViolation>,
     pub recommendations: Vec<String>,
 }
@@ -473,14 +1913,14 @@ pub struct ComplianceViolation {
     pub expected_value: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum ViolationSeverity {
     Critical,
     Warning,
     Info,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum ViolationCategory {
     VideoCodec,
     AudioCodec,
@@ -488,12 +1928,138 @@ pub enum ViolationCategory {
     FrameRate,
     Bitrate,
     Container,
+    /// MP4/MOV index stored after the media data
+    Faststart,
     ColorSpace,
     HDR,
     Profile,
+    Keyframe,
+    AspectRatio,
+    Rotation,
+    Interlacing,
+    ChromaSubsampling,
+    BitDepth,
     Audio,
 }
 
+impl VideoMetadata {
+    /// Interlaced according to idet when it ran, otherwise the signalled field order
+    pub fn is_interlaced(&self) -> bool {
+        match &self.interlace {
+            Some(interlace) => interlace.is_interlaced(),
+            None => self.field_order.is_interlaced(),
+        }
+    }
+
+    /// Field order to deinterlace with; idet wins over the stream flags
+    pub fn interlaced_field_order(&self) -> FieldOrder {
+        match &self.interlace {
+            Some(interlace) if interlace.is_interlaced() => interlace.field_order(),
+            _ => self.field_order,
+        }
+    }
+
+    /// True when frame timestamps (or average vs nominal rate) show variable frame rate
+    pub fn is_variable_frame_rate(&self) -> bool {
+        self.frame_timing
+            .as_ref()
+            .is_some_and(|timing| timing.variable)
+    }
+
+    /// True when players turn the frame sideways (90° or 270°)
+    pub fn is_rotated_sideways(&self) -> bool {
+        self.rotation % 180 == 90
+    }
+
+    /// Frame size as players show it, after applying the rotation
+    pub fn effective_resolution(&self) -> Resolution {
+        rotate_resolution(self.resolution, self.rotation)
+    }
+
+    /// Width over height as displayed, accounting for non-square pixels and rotation
+    pub fn display_aspect(&self) -> Option<f64> {
+        if !self.resolution.is_known() {
+            return None;
+        }
+        let Resolution { width, height } = self.resolution;
+        let (sar_num, sar_den) = parse_ratio(&self.sample_aspect_ratio).unwrap_or((1, 1));
+        let aspect = (width as f64 * sar_num as f64) / (height as f64 * sar_den as f64);
+        Some(if self.is_rotated_sideways() {
+            1.0 / aspect
+        } else {
+            aspect
+        })
+    }
+
+    /// Upright frame size after resampling to square pixels, as the scaler sees it
+    /// once rotation is applied and anamorphic video is corrected
+    /// (`scale=trunc(iw*sar/2)*2:ih`)
+    pub fn display_resolution(&self) -> Resolution {
+        let square = match parse_ratio(&self.sample_aspect_ratio) {
+            Some((num, den)) if num != den => {
+                let width = self.resolution.width as u64 * num as u64 / den as u64;
+                Resolution::new((width / 2 * 2) as u32, self.resolution.height)
+            }
+            _ => self.resolution,
+        };
+        rotate_resolution(square, self.rotation)
+    }
+
+    /// False only when the sample aspect ratio is known and not 1:1
+    pub fn has_square_pixels(&self) -> bool {
+        parse_ratio(&self.sample_aspect_ratio).is_none_or(|(num, den)| num == den)
+    }
+
+    /// Display aspect ratio in lowest terms, e.g. "16:9", preferring ffprobe's value
+    /// (which describes the stored frame, so it is flipped for sideways rotation)
+    pub fn display_aspect_ratio_name(&self) -> String {
+        let (num, den) = match parse_ratio(&self.display_aspect_ratio) {
+            Some((num, den)) => (num as u64, den as u64),
+            None if self.resolution.is_known() => {
+                let Resolution { width, height } = self.resolution;
+                let (sar_num, sar_den) = parse_ratio(&self.sample_aspect_ratio).unwrap_or((1, 1));
+                let num = width as u64 * sar_num as u64;
+                let den = height as u64 * sar_den as u64;
+                let divisor = gcd(num, den);
+                (num / divisor, den / divisor)
+            }
+            None => return "unknown".to_string(),
+        };
+        if self.is_rotated_sideways() {
+            format!("{}:{}", den, num)
+        } else {
+            format!("{}:{}", num, den)
+        }
+    }
+
+    /// Dynamic range classification from transfer characteristics and side data
+    pub fn hdr_format(&self) -> HdrFormat {
+        hdr::classify(&self.color_transfer, &self.hdr)
+    }
+
+    /// Describe the HDR signalling, e.g. "HDR10 (smpte2084, bt2020, MaxCLL 1000, MaxFALL 400)"
+    pub fn hdr_summary(&self) -> String {
+        let mut details = vec![self.color_transfer.clone(), self.color_primaries.clone()];
+        let side_data = self.hdr.summary();
+        if !side_data.is_empty() {
+            details.push(side_data);
+        }
+        format!("{} ({})", self.hdr_format(), details.join(", "))
+    }
+
+    /// Video stream bitrate, estimated from the container bitrate minus the
+    /// audio bitrate when the stream does not report one (e.g. MKV)
+    pub fn effective_video_bitrate(&self) -> Option<u64> {
+        if self.video_bitrate > 0 {
+            Some(self.video_bitrate)
+        } else if self.bitrate > 0 {
+            Some(self.bitrate.saturating_sub(self.audio_bitrate))
+        } else {
+            None
+        }
+    }
+}
+
 impl ProcessingSummary {
     pub fn new() -> Self {
         Default::default()
@@ -510,7 +2076,7 @@ impl ProcessingSummary {
             .or_insert(0) += 1;
         *self
             .resolutions
-            .entry(metadata.resolution.clone())
+            .entry(metadata.effective_resolution().to_string())
             .or_insert(0) += 1;
     }
 
@@ -692,7 +2258,8 @@ pub fn analyze_video(path: &Path) -> Result<VideoMetadata, VideoError> {
             path.to_str()
                 .ok_or_else(|| VideoError::InvalidPath(path.display().to_string()))?,
         ])
-        .output()?;
+        .output()
+        .map_err(|e| VideoError::FFmpeg(format!("failed to run ffprobe: {}", e)))?;
 
     spinner.finish_and_clear();
 
@@ -726,17 +2293,110 @@ pub fn analyze_video(path: &Path) -> Result<VideoMetadata, VideoError> {
         .unwrap_or("unknown")
         .to_lowercase();
 
+    let pix_fmt = video_stream["pix_fmt"]
+        .as_str()
+        .unwrap_or("unknown")
+        .to_string();
+    let (chroma_subsampling, mut bit_depth) = parse_pixel_format(&pix_fmt).unwrap_or_default();
+    // bits_per_raw_sample is more precise when the decoder reports it
+    if let Some(bits) = video_stream["bits_per_raw_sample"]
+        .as_str()
+        .and_then(|bits| bits.parse::<u8>().ok())
+        .filter(|bits| *bits > 0)
+    {
+        bit_depth = bits;
+    }
+
+    let color_transfer = video_stream["color_transfer"]
+        .as_str()
+        .unwrap_or("unknown")
+        .to_lowercase();
+    let color_primaries = video_stream["color_primaries"]
+        .as_str()
+        .unwrap_or("unknown")
+        .to_lowercase();
+
+    let faststart = if matches!(container.as_str(), "mp4" | "m4v" | "mov") {
+        quicktime::probe_faststart(path).unwrap_or_else(|e| {
+            warn!(?e, "atom layout scan failed");
+            None
+        })
+    } else {
+        None
+    };
+
+    // Containers carry HDR side data on the stream; HEVC/AV1 also carry it
+    // in-band, so look at the first frame when the transfer suggests HDR
+    let mut hdr = HdrMetadata::default();
+    hdr.merge_side_data(&video_stream["side_data_list"]);
+    if color_transfer == hdr::TRANSFER_PQ || color_transfer == hdr::TRANSFER_HLG {
+        match hdr::probe_frame_side_data(path) {
+            Ok(frame_hdr) => {
+                hdr.mastering_display = hdr.mastering_display.or(frame_hdr.mastering_display);
+                hdr.content_light_level = hdr.content_light_level.or(frame_hdr.content_light_level);
+                hdr.dolby_vision = hdr.dolby_vision.or(frame_hdr.dolby_vision);
+                hdr.hdr10_plus |= frame_hdr.hdr10_plus;
+            }
+            Err(e) => warn!(?e, "HDR side data probe failed"),
+        }
+    }
+
+    // AAC must be CBR, which needs the audio packet sizes; a failure here is not fatal
+    let audio_packets =
+        if audio_stream.and_then(|stream| stream["codec_name"].as_str()) == Some("aac") {
+            match stream_analysis::probe_audio_packets(path) {
+                Ok(packets) => stream_analysis::analyze_audio_bitrate(&packets),
+                Err(e) => {
+                    warn!(?e, "audio bitrate analysis failed");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+    let fps = parse_frame_rate(&video_stream["r_frame_rate"]);
+    let avg_fps = parse_frame_rate(&video_stream["avg_frame_rate"]);
+
+    // Keyframe spacing and frame timing need a packet scan; a failure here is not fatal
+    let (gop, frame_timing) = match stream_analysis::probe_video_packets(path) {
+        Ok(packets) => (
+            stream_analysis::analyze_gop(&packets),
+            stream_analysis::analyze_frame_timing(&packets, fps, avg_fps),
+        ),
+        Err(e) => {
+            warn!(?e, "keyframe analysis failed");
+            (
+                None,
+                stream_analysis::analyze_frame_timing(&[], fps, avg_fps),
+            )
+        }
+    };
+
     // Extract comprehensive video metadata
     Ok(VideoMetadata {
+        file_name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
         codec: video_stream["codec_name"]
             .as_str()
             .unwrap_or("unknown")
             .to_string(),
-        resolution: format!(
-            "{}x{}",
-            video_stream["width"].as_u64().unwrap_or(0),
-            video_stream["height"].as_u64().unwrap_or(0)
+        resolution: Resolution::new(
+            video_stream["width"].as_u64().unwrap_or(0) as u32,
+            video_stream["height"].as_u64().unwrap_or(0) as u32,
         ),
+        sample_aspect_ratio: video_stream["sample_aspect_ratio"]
+            .as_str()
+            .unwrap_or("")
+            .to_string(),
+        display_aspect_ratio: video_stream["display_aspect_ratio"]
+            .as_str()
+            .unwrap_or("")
+            .to_string(),
+        rotation: stream_rotation(video_stream),
+        field_order: FieldOrder::from_ffprobe(video_stream["field_order"].as_str().unwrap_or("")),
         duration: json["format"]["duration"]
             .as_str()
             .and_then(|d| d.parse::<f64>().ok())
@@ -745,27 +2405,15 @@ pub fn analyze_video(path: &Path) -> Result<VideoMetadata, VideoError> {
             .as_str()
             .and_then(|b| b.parse::<u64>().ok())
             .unwrap_or(0),
+        video_bitrate: video_stream["bit_rate"]
+            .as_str()
+            .and_then(|b| b.parse::<u64>().ok())
+            .unwrap_or(0),
         size: json["format"]["size"]
             .as_str()
             .and_then(|s| s.parse::<u64>().ok())
             .unwrap_or(0),
-        fps: video_stream["r_frame_rate"]
-            .as_str()
-            .and_then(|fps| {
-                let parts: Vec<&str> = fps.split('/').collect();
-                if parts.len() == 2 {
-                    let num: f64 = parts[0].parse().ok()?;
-                    let den: f64 = parts[1].parse().ok()?;
-                    if den != 0.0 {
-                        Some(num / den)
-                    } else {
-                        None
-                    }
-                } else {
-                    fps.parse().ok()
-                }
-            })
-            .unwrap_or(0.0),
+        fps,
         audio_codec: audio_stream
             .and_then(|stream| stream["codec_name"].as_str())
             .unwrap_or("none")
@@ -778,17 +2426,51 @@ pub fn analyze_video(path: &Path) -> Result<VideoMetadata, VideoError> {
             .and_then(|stream| stream["bit_rate"].as_str())
             .and_then(|rate| rate.parse().ok())
             .unwrap_or(0),
+        audio_sample_fmt: audio_stream
+            .and_then(|stream| stream["sample_fmt"].as_str())
+            .unwrap_or("unknown")
+            .to_string(),
+        audio_bit_depth: audio_stream
+            .and_then(|stream| {
+                let codec = stream["codec_name"].as_str()?;
+                Some(audio_bit_depth(codec, stream))
+            })
+            .unwrap_or(0),
+        audio_channels: audio_stream
+            .and_then(|stream| stream["channels"].as_u64())
+            .and_then(|channels| u32::try_from(channels).ok())
+            .unwrap_or(0),
+        audio_channel_layout: audio_stream
+            .and_then(|stream| stream["channel_layout"].as_str())
+            .unwrap_or("unknown")
+            .to_string(),
+        audio_packets,
         container,
+        faststart,
         profile: video_stream["profile"]
             .as_str()
             .unwrap_or("unknown")
             .to_string()
             .to_lowercase(),
+        // ffprobe reports -99 when the level is unknown
+        level: video_stream["level"]
+            .as_i64()
+            .and_then(|level| u32::try_from(level).ok())
+            .unwrap_or(0),
         color_space: video_stream["color_space"]
             .as_str()
             .unwrap_or("unknown")
             .to_string()
             .to_lowercase(),
+        color_transfer,
+        color_primaries,
+        hdr,
+        pix_fmt,
+        chroma_subsampling,
+        bit_depth,
+        gop,
+        frame_timing,
+        interlace: None,
     })
 }
 
@@ -797,6 +2479,9 @@ pub fn fix_video_compliance(
     input: &Path,
     output_dir: &Path,
     compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    encoder: EncoderBackend,
 ) -> Result<PathBuf, VideoError> {
     info!(
         "Starting intelligent compliance fixing for: {}",
@@ -804,7 +2489,7 @@ pub fn fix_video_compliance(
     );
 
     // Generate output filename with compliance suffix
-    let output_filename = generate_compliance_output_filename(input, compliance_result);
+    let output_filename = generate_compliance_output_filename(input, compliance_result, standards);
     let output_path = output_dir.join(&output_filename);
 
     println!(
@@ -816,19 +2501,29 @@ pub fn fix_video_compliance(
     println!("Violations to fix: {}", compliance_result.violations.len());
 
     // Build FFmpeg arguments based on violations
-    let mut args = vec!["-i".to_string(), input.to_str().unwrap().to_string()];
+    let mut args = Vec::new();
 
-    // Add hardware decoding if beneficial
+    // Add hardware decoding if beneficial (an input option, so it precedes -i)
     if should_use_hw_decode(compliance_result) {
-        args.extend(["-hwaccel".to_string(), "cuda".to_string()]);
+        args.extend(encoder.hwaccel_args());
     }
+    args.extend(["-i".to_string(), input.to_str().unwrap().to_string()]);
 
     // Video encoding settings
-    let video_fixes = generate_video_fixes(compliance_result);
+    let video_fixes = generate_video_fixes(compliance_result, metadata, standards, encoder);
     args.extend(video_fixes);
 
     // Audio encoding settings
-    let audio_fixes = generate_audio_fixes(compliance_result);
+    let output_container = output_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("mp4");
+    let audio_fixes =
+        generate_audio_fixes(compliance_result, metadata, standards, output_container);
+    let content_type = detect_content_type(metadata, Path::new(&metadata.file_name));
+    let converts_frame_rate =
+        frame_rate_target(compliance_result, metadata, standards, &content_type).is_some();
+    args.extend(audio_sync_args(converts_frame_rate, &audio_fixes));
     args.extend(audio_fixes);
 
     // Quality preservation settings
@@ -846,25 +2541,30 @@ pub fn fix_video_compliance(
 }
 
 /// Legacy basic conversion for backward compatibility
-pub fn convert_video(input: &Path, h264_dir: &Path) -> Result<(), VideoError> {
+pub fn convert_video(
+    input: &Path,
+    h264_dir: &Path,
+    encoder: EncoderBackend,
+) -> Result<(), VideoError> {
     let output = h264_dir.join(input.file_name().unwrap());
     println!("\nConverting: {} -> {}", input.display(), output.display());
     println!("----------------------------------------");
 
-    let args = vec![
-        "-i",
-        input.to_str().unwrap(),
-        "-c:v",
-        "h264_nvenc",
-        "-preset",
-        "p7",
-        "-c:a",
-        "aac",
-        "-b:a",
-        "320k",
-        "-y",
-        output.to_str().unwrap(),
+    let mut args = vec![
+        "-i".to_string(),
+        input.to_str().unwrap().to_string(),
+        "-c:v".to_string(),
+        encoder.ffmpeg_name().to_string(),
     ];
+    args.extend(encoder.max_quality_args(18));
+    args.extend([
+        "-c:a".to_string(),
+        "aac".to_string(),
+        "-b:a".to_string(),
+        "320k".to_string(),
+        "-y".to_string(),
+        output.to_str().unwrap().to_string(),
+    ]);
 
     let mut child = Command::new("ffmpeg")
         .args(&args)
@@ -892,9 +2592,11 @@ pub fn convert_video(input: &Path, h264_dir: &Path) -> Result<(), VideoError> {
 fn generate_compliance_output_filename(
     input: &Path,
     compliance_result: &ComplianceResult,
+    standards: &ContentStandards,
 ) -> String {
     let stem = input.file_stem().unwrap().to_str().unwrap();
     let ext = input.extension().unwrap_or_default().to_str().unwrap();
+    let container = if ext.is_empty() { "mp4" } else { ext };
 
     let mut suffix = String::from(".compliant");
 
@@ -913,10 +2615,9 @@ fn generate_compliance_output_filename(
     {
         suffix.push_str(".h264");
     }
-    if compliance_result.violations.iter().any(|v| {
-        v.category == ViolationCategory::Audio || v.category == ViolationCategory::AudioCodec
-    }) {
-        suffix.push_str(".aac");
+    if audio_needs_reencode(compliance_result) {
+        suffix.push('.');
+        suffix.push_str(resolved_audio_target(&standards.audio, container).codec_family());
     }
     if compliance_result.violations.iter().any(|v| {
         v.category == ViolationCategory::ColorSpace || v.category == ViolationCategory::HDR
@@ -924,12 +2625,7 @@ fn generate_compliance_output_filename(
         suffix.push_str(".rec709");
     }
 
-    format!(
-        "{}{}.{}",
-        stem,
-        suffix,
-        if ext.is_empty() { "mp4" } else { ext }
-    )
+    format!("{}{}.{}", stem, suffix, container)
 }
 
 /// Determine if hardware decoding should be used
@@ -937,21 +2633,23 @@ fn should_use_hw_decode(compliance_result: &ComplianceResult) -> bool {
     // Use hardware decode for large files or when significant processing is needed
     compliance_result.violations.len() > 2
         || compliance_result.violations.iter().any(|v| {
-            v.category == ViolationCategory::Resolution
-                || v.category == ViolationCategory::ColorSpace
-                || v.category == ViolationCategory::HDR
+            v.category == ViolationCategory::Resolution || v.category == ViolationCategory::HDR
         })
 }
 
 /// Generate video encoding arguments based on compliance violations
-fn generate_video_fixes(compliance_result: &ComplianceResult) -> Vec<String> {
+fn generate_video_fixes(
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    encoder: EncoderBackend,
+) -> Vec<String> {
     let mut args = Vec::new();
 
-    // Check if codec fixing is needed
-    let needs_codec_fix = compliance_result
-        .violations
-        .iter()
-        .any(|v| v.category == ViolationCategory::VideoCodec);
+    // Check if codec fixing is needed (an unsupported profile also needs a re-encode)
+    let needs_codec_fix = compliance_result.violations.iter().any(|v| {
+        v.category == ViolationCategory::VideoCodec || v.category == ViolationCategory::Profile
+    });
 
     // Check if resolution fixing is needed
     let needs_resolution_fix = compliance_result
@@ -959,37 +2657,93 @@ fn generate_video_fixes(compliance_result: &ComplianceResult) -> Vec<String> {
         .iter()
         .any(|v| v.category == ViolationCategory::Resolution);
 
-    // Check if quality fixes are needed
-    let needs_quality_fix = compliance_result.violations.iter().any(|v| {
-        v.category == ViolationCategory::ColorSpace || v.category == ViolationCategory::HDR
+    // Check if HDR or wide-gamut video needs converting to SDR
+    let needs_quality_fix = compliance_result
+        .violations
+        .iter()
+        .any(|v| v.category == ViolationCategory::HDR);
+
+    // Check if the pixel format needs converting (covered by -pix_fmt yuv420p)
+    let needs_pixel_format_fix = compliance_result.violations.iter().any(|v| {
+        v.category == ViolationCategory::ChromaSubsampling
+            || v.category == ViolationCategory::BitDepth
     });
 
-    if needs_codec_fix || needs_resolution_fix || needs_quality_fix {
-        // Use H.264 NVENC with high quality settings
+    // Check if display-matrix rotation needs baking into the pixels. Decoding
+    // with ffmpeg's default autorotate turns the frames upright and drops the
+    // display matrix from the output, so re-encoding is all it takes.
+    let needs_rotation_fix = compliance_result
+        .violations
+        .iter()
+        .any(|v| v.category == ViolationCategory::Rotation);
+
+    // Check if interlaced video needs deinterlacing
+    let needs_deinterlace = compliance_result
+        .violations
+        .iter()
+        .any(|v| v.category == ViolationCategory::Interlacing);
+
+    // Variable or out-of-spec frame rates are converted to an allowed constant rate
+    let content_type = detect_content_type(metadata, Path::new(&metadata.file_name));
+    let frame_rate = frame_rate_target(compliance_result, metadata, standards, &content_type);
+
+    if needs_codec_fix
+        || needs_resolution_fix
+        || needs_quality_fix
+        || needs_pixel_format_fix
+        || needs_rotation_fix
+        || needs_deinterlace
+        || frame_rate.is_some()
+    {
+        // Use H.264 with the highest quality preset in constant quality mode
+        args.extend(["-c:v".to_string(), encoder.ffmpeg_name().to_string()]);
+        args.extend(encoder.max_quality_args(18));
         args.extend([
-            "-c:v".to_string(),
-            "h264_nvenc".to_string(),
-            "-preset".to_string(),
-            "p7".to_string(), // Highest quality preset
-            "-cq".to_string(),
-            "18".to_string(), // Constant quality mode
             "-profile:v".to_string(),
             "high".to_string(),
             "-pix_fmt".to_string(),
             "yuv420p".to_string(),
         ]);
 
+        // Deinterlace before any other filter; rotated sources arrive upright
+        // from ffmpeg's autorotate
+        let mut filters = Vec::new();
+        if needs_deinterlace {
+            filters.push(interlace::deinterlace_filter(
+                metadata.interlaced_field_order(),
+            ));
+        }
+
+        filters.extend(frame_rate.map(|rate| frame_rate_filter(metadata, rate, standards)));
+
+        // Tone map or convert the gamut before scaling, so scaling works on SDR pixels
+        filters.extend(color_conversion_filters(
+            compliance_result,
+            metadata,
+            standards,
+        ));
+
         // Add resolution scaling if needed
         if needs_resolution_fix {
-            // Find target resolution from violations
-            let target_resolution = determine_target_resolution(compliance_result);
-            if let Some(res) = target_resolution {
-                args.extend(["-vf".to_string(), format!("scale={}", res)]);
+            // Targets are chosen from the display size, so resample anamorphic video first
+            if !metadata.has_square_pixels() {
+                filters.push("scale=trunc(iw*sar/2)*2:ih".to_string());
+                filters.push("setsar=1".to_string());
             }
+            filters.extend(resolution_filters(metadata, standards));
+        }
+
+        if !filters.is_empty() {
+            args.extend(["-vf".to_string(), filters.join(",")]);
         }
 
-        // Add color space conversion if needed
-        if needs_quality_fix {
+        // Tag the output as Rec. 709, including untagged sources
+        if needs_quality_fix
+            || compliance_result
+                .violations
+                .iter()
+                .any(|v| v.category == ViolationCategory::ColorSpace)
+        {
             args.extend([
                 "-colorspace".to_string(),
                 "bt709".to_string(),
@@ -1008,47 +2762,218 @@ fn generate_video_fixes(compliance_result: &ComplianceResult) -> Vec<String> {
 }
 
 /// Generate audio encoding arguments based on compliance violations
-fn generate_audio_fixes(compliance_result: &ComplianceResult) -> Vec<String> {
-    let mut args = Vec::new();
-
-    let needs_audio_fix = compliance_result.violations.iter().any(|v| {
-        v.category == ViolationCategory::Audio || v.category == ViolationCategory::AudioCodec
-    });
-
-    if needs_audio_fix {
-        // Use PCM audio (pcm_s24le) as per content delivery spec and DaVinci Resolve compatibility
-        args.extend([
-            "-c:a".to_string(),
-            "pcm_s24le".to_string(), // 24-bit PCM (preferred format)
-            "-ar".to_string(),
-            "48000".to_string(), // 48kHz sample rate
-            "-ac".to_string(),
-            "2".to_string(), // Stereo
-        ]);
+fn generate_audio_fixes(
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    output_container: &str,
+) -> Vec<String> {
+    if audio_needs_reencode(compliance_result) {
+        audio_encode_args(metadata, &standards.audio, output_container)
     } else {
         // No audio fixes needed, copy stream
-        args.extend(["-c:a".to_string(), "copy".to_string()]);
+        vec!["-c:a".to_string(), "copy".to_string()]
     }
+}
 
-    args
+/// True when the audio stream has to be re-encoded. Info violations, such as
+/// an acceptable but not preferred codec like AAC, leave the stream copied.
+fn audio_needs_reencode(compliance_result: &ComplianceResult) -> bool {
+    compliance_result.violations.iter().any(|v| {
+        (v.category == ViolationCategory::Audio || v.category == ViolationCategory::AudioCodec)
+            && !matches!(v.severity, ViolationSeverity::Info)
+    })
 }
 
-/// Determine target resolution from compliance violations
-fn determine_target_resolution(compliance_result: &ComplianceResult) -> Option<String> {
-    // Look for resolution recommendations in violations
-    for violation in &compliance_result.violations {
-        if violation.category == ViolationCategory::Resolution {
-            if violation.expected_value.contains("1920x1080") {
-                return Some("1920:1080".to_string());
-            }
-            if violation.expected_value.contains("1280x720") {
-                return Some("1280:720".to_string());
+/// Codec re-encoded audio is written in: `audio.target_codec`, with ALAC in
+/// place of PCM in MP4
+fn resolved_audio_target(audio: &AudioStandards, output_container: &str) -> AudioTarget {
+    match audio.target_codec {
+        AudioTarget::Pcm if output_container.eq_ignore_ascii_case("mp4") => AudioTarget::Alac,
+        target => target,
+    }
+}
+
+/// Encoder settings for re-encoded audio under `audio.target_codec`. The
+/// source sample rate is kept when allowed (otherwise 48 kHz), and lossless
+/// output keeps 16-bit sources at 16-bit rather than padding them to 24.
+fn audio_encode_args(
+    metadata: &VideoMetadata,
+    audio: &AudioStandards,
+    output_container: &str,
+) -> Vec<String> {
+    let sample_rate = if audio.sample_rates.contains(&metadata.audio_sample_rate) {
+        metadata.audio_sample_rate
+    } else if audio.sample_rates.contains(&48000) || audio.sample_rates.is_empty() {
+        48000
+    } else {
+        audio.sample_rates[0]
+    };
+    let bit_depth = if metadata.audio_bit_depth == 16 && audio.bit_depths.contains(&16) {
+        16
+    } else {
+        audio.bit_depths.iter().copied().max().unwrap_or(24).min(24)
+    };
+
+    let mut args = match resolved_audio_target(audio, output_container) {
+        AudioTarget::Pcm => vec![
+            "-c:a".to_string(),
+            if bit_depth == 16 {
+                "pcm_s16le"
+            } else {
+                "pcm_s24le"
             }
-        }
+            .to_string(),
+        ],
+        // The ALAC encoder stores 24-bit samples from s32p
+        AudioTarget::Alac => vec![
+            "-c:a".to_string(),
+            "alac".to_string(),
+            "-sample_fmt".to_string(),
+            if bit_depth == 16 { "s16p" } else { "s32p" }.to_string(),
+        ],
+        AudioTarget::Aac => vec![
+            "-c:a".to_string(),
+            "aac".to_string(),
+            "-b:a".to_string(),
+            format!(
+                "{}k",
+                audio.bitrate_ranges.get("aac").copied().unwrap_or(320)
+            ),
+        ],
+    };
+    args.extend([
+        "-ar".to_string(),
+        sample_rate.to_string(),
+        "-ac".to_string(),
+        "2".to_string(), // Stereo
+    ]);
+    args
+}
+
+/// Pick the allowed resolution closest to the source's orientation and aspect ratio
+fn determine_target_resolution(
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+) -> Option<Resolution> {
+    resolution::select_target(
+        metadata.display_resolution(),
+        &standards.video.preferred_resolutions,
+        &standards.video.acceptable_resolutions,
+    )
+}
+
+/// Scaling filters that bring the video to its target resolution under the
+/// standards' scaling policy
+fn resolution_filters(metadata: &VideoMetadata, standards: &ContentStandards) -> Vec<String> {
+    match determine_target_resolution(metadata, standards) {
+        Some(target) => resolution::scale_filters(
+            metadata.display_resolution(),
+            target,
+            standards.video.scaling_policy,
+        ),
+        None => Vec::new(),
+    }
+}
+
+/// Filters that convert HDR or wide-gamut video to SDR Rec. 709, when the
+/// compliance check flagged its dynamic range or color space
+fn color_conversion_filters(
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+) -> Vec<String> {
+    if !compliance_result
+        .violations
+        .iter()
+        .any(|v| v.category == ViolationCategory::HDR)
+    {
+        return Vec::new();
+    }
+    hdr::sdr_conversion_filters(
+        &metadata.color_space,
+        &metadata.color_transfer,
+        &metadata.color_primaries,
+        metadata.hdr_format(),
+        standards.quality.tone_mapping,
+    )
+}
+
+/// Constant rate to convert the video to when its frame rate was flagged.
+/// Variable frame rate is conformed to the allowed rate nearest its average;
+/// a rate outside every allowed list goes to its `conversion_frame_rate`.
+/// Rates preferred for other content types are left alone.
+fn frame_rate_target(
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    content_type: &ContentType,
+) -> Option<f64> {
+    if !compliance_result
+        .violations
+        .iter()
+        .any(|v| v.category == ViolationCategory::FrameRate)
+    {
+        return None;
+    }
+    let allowed = standards.video.frame_rates_for(content_type);
+
+    if let Some(timing) = metadata.frame_timing.as_ref().filter(|t| t.variable) {
+        let average = if timing.average_fps > 0.0 {
+            timing.average_fps
+        } else {
+            timing.nominal_fps
+        };
+        return nearest_frame_rate(average, &allowed);
+    }
+
+    if metadata.fps <= 0.0
+        || standards
+            .video
+            .preferred_frame_rates
+            .iter()
+            .any(|rate| frame_rate_matches(metadata.fps, *rate))
+    {
+        return None;
+    }
+    conversion_frame_rate(metadata.fps, &allowed)
+}
+
+/// Filter converting the video to `target` fps. Whole-number ratios and VFR
+/// footage use the `fps` filter, which drops frames without touching the
+/// timestamps of the rest, so stream-copied audio stays in sync.
+fn frame_rate_filter(
+    metadata: &VideoMetadata,
+    target: f64,
+    standards: &ContentStandards,
+) -> String {
+    let rate = frame_rate_expr(target);
+    if standards.video.frame_rate_conversion == FrameRateConversion::Interpolate
+        && !metadata.is_variable_frame_rate()
+        && !is_integer_ratio(metadata.fps, target)
+    {
+        format!(
+            "minterpolate=fps={}:mi_mode=mci:mc_mode=aobmc:me_mode=bidir:vsbmc=1",
+            rate
+        )
+    } else {
+        format!("fps={}", rate)
     }
+}
 
-    // Default to 1080p if no specific recommendation
-    Some("1920:1080".to_string())
+/// Audio filter that pads or trims re-encoded audio to the video timestamps
+/// after a frame rate change; copied audio keeps its own timestamps
+fn audio_sync_args(converts_frame_rate: bool, audio_fixes: &[String]) -> Vec<String> {
+    let copies_audio = audio_fixes
+        .windows(2)
+        .any(|pair| pair[0] == "-c:a" && pair[1] == "copy");
+    if !converts_frame_rate || copies_audio {
+        return Vec::new();
+    }
+    vec![
+        "-af".to_string(),
+        "aresample=async=1:first_pts=0".to_string(),
+    ]
 }
 
 /// Execute compliance conversion with progress tracking
@@ -1094,79 +3019,130 @@ fn execute_compliance_conversion(
 }
 
 /// Content type detection for optimal encoding settings
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum ContentType {
     ScreenCapture,
     LiveAction,
     Animation,
     Presentation,
+    /// Portrait (9:16) footage, usually recorded on a phone
+    Vertical,
     Unknown,
 }
 
+impl ContentType {
+    /// Key used for this content type in `content_frame_rates`
+    pub fn standards_key(&self) -> Option<&'static str> {
+        match self {
+            ContentType::ScreenCapture | ContentType::Presentation => Some("screen_capture"),
+            ContentType::LiveAction | ContentType::Animation => Some("live_action"),
+            ContentType::Vertical => Some("vertical"),
+            ContentType::Unknown => None,
+        }
+    }
+
+    /// Key used for this content type in `bitrate_ranges`; the spec has no
+    /// separate range for vertical video, which is delivered at live action rates
+    pub fn bitrate_key(&self) -> Option<&'static str> {
+        match self {
+            ContentType::Vertical => Some("live_action"),
+            _ => self.standards_key(),
+        }
+    }
+}
+
 /// Analyze video content to determine optimal processing approach
 pub fn detect_content_type(metadata: &VideoMetadata, path: &Path) -> ContentType {
-    let filename = path.file_name().unwrap().to_str().unwrap().to_lowercase();
+    detect_content_type_with_reason(metadata, path).0
+}
 
-    // Filename-based heuristics
-    if filename.contains("screen") || filename.contains("capture") || filename.contains("recording")
-    {
-        return ContentType::ScreenCapture;
-    }
+/// Detect the content type along with the heuristic that decided it
+pub fn detect_content_type_with_reason(
+    metadata: &VideoMetadata,
+    path: &Path,
+) -> (ContentType, String) {
+    let filename = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("")
+        .to_lowercase();
 
-    if filename.contains("presentation") || filename.contains("slide") || filename.contains("demo")
-    {
-        return ContentType::Presentation;
+    // Portrait footage follows the vertical video rules whatever it shows
+    let display = metadata.display_resolution();
+    if display.orientation() == Orientation::Portrait {
+        return (ContentType::Vertical, format!("portrait {} frame", display));
     }
 
-    if filename.contains("cartoon") || filename.contains("animated") || filename.contains("anime") {
-        return ContentType::Animation;
+    // Filename-based heuristics
+    let filename_hints = [
+        (
+            ["screen", "capture", "recording"],
+            ContentType::ScreenCapture,
+        ),
+        (["presentation", "slide", "demo"], ContentType::Presentation),
+        (["cartoon", "animated", "anime"], ContentType::Animation),
+    ];
+    for (keywords, content_type) in filename_hints {
+        if let Some(keyword) = keywords.iter().find(|k| filename.contains(*k)) {
+            return (content_type, format!("file name contains '{}'", keyword));
+        }
     }
 
     // Metadata-based analysis
     // High framerate usually indicates live action
     if metadata.fps > 50.0 {
-        return ContentType::LiveAction;
+        return (
+            ContentType::LiveAction,
+            "frame rate above 50 fps".to_string(),
+        );
     }
 
     // Very low framerates suggest screen capture or presentations
     if metadata.fps < 20.0 {
-        return ContentType::ScreenCapture;
+        return (
+            ContentType::ScreenCapture,
+            "frame rate below 20 fps".to_string(),
+        );
     }
 
     // Resolution-based heuristics
-    if metadata.resolution.contains("1920x1080") && metadata.fps >= 24.0 && metadata.fps <= 30.0 {
-        return ContentType::LiveAction;
+    if metadata.resolution == Resolution::FULL_HD && metadata.fps >= 24.0 && metadata.fps <= 30.0 {
+        return (ContentType::LiveAction, "1080p at 24-30 fps".to_string());
     }
 
     // Default to screen capture for most content
-    ContentType::ScreenCapture
+    (
+        ContentType::ScreenCapture,
+        "default for unclassified content".to_string(),
+    )
 }
 
 /// Get optimal bitrate based on content type and resolution
-pub fn get_optimal_bitrate(content_type: &ContentType, resolution: &str) -> u32 {
+pub fn get_optimal_bitrate(content_type: &ContentType, resolution: Resolution) -> u32 {
+    let resolution = resolution.landscape();
     match content_type {
         ContentType::ScreenCapture | ContentType::Presentation => {
-            if resolution.contains("1920x1080") {
+            if resolution == Resolution::FULL_HD {
                 6000 // 6Mbps for 1080p screen capture
-            } else if resolution.contains("1280x720") {
+            } else if resolution == Resolution::HD {
                 4000 // 4Mbps for 720p screen capture
             } else {
                 3000 // 3Mbps for other resolutions
             }
         }
-        ContentType::LiveAction => {
-            if resolution.contains("1920x1080") {
+        ContentType::LiveAction | ContentType::Vertical => {
+            if resolution == Resolution::FULL_HD {
                 12000 // 12Mbps for 1080p live action
-            } else if resolution.contains("1280x720") {
+            } else if resolution == Resolution::HD {
                 8000 // 8Mbps for 720p live action
             } else {
                 5000 // 5Mbps for other resolutions
             }
         }
         ContentType::Animation => {
-            if resolution.contains("1920x1080") {
+            if resolution == Resolution::FULL_HD {
                 8000 // 8Mbps for 1080p animation
-            } else if resolution.contains("1280x720") {
+            } else if resolution == Resolution::HD {
                 6000 // 6Mbps for 720p animation
             } else {
                 4000 // 4Mbps for other resolutions
@@ -1174,9 +3150,9 @@ pub fn get_optimal_bitrate(content_type: &ContentType, resolution: &str) -> u32
         }
         ContentType::Unknown => {
             // Conservative middle ground
-            if resolution.contains("1920x1080") {
+            if resolution == Resolution::FULL_HD {
                 8000
-            } else if resolution.contains("1280x720") {
+            } else if resolution == Resolution::HD {
                 6000
             } else {
                 4000
@@ -1191,208 +3167,868 @@ pub fn fix_video_compliance_optimized(
     output_dir: &Path,
     compliance_result: &ComplianceResult,
     metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    encoder: EncoderBackend,
 ) -> Result<PathBuf, VideoError> {
     info!(
         "Starting content-aware compliance fixing for: {}",
         input.display()
     );
 
-    // Detect content type for optimization
-    let content_type = detect_content_type(metadata, input);
-    info!("Detected content type: {:?}", content_type);
-
-    // Generate output filename with content type indicator
-    let output_filename =
-        generate_optimized_output_filename(input, compliance_result, &content_type);
-    let output_path = output_dir.join(&output_filename);
+    let plan = FixPlan::new(
+        input,
+        output_dir,
+        compliance_result,
+        metadata,
+        standards,
+        encoder,
+    );
+    info!("Detected content type: {:?}", plan.content_type);
 
     println!(
         "\n{}",
         "🧠 Content-Aware Compliance Fixing".bright_blue().bold()
     );
     println!("Input:        {}", input.display());
-    println!("Output:       {}", output_path.display());
-    println!("Content Type: {:?}", content_type);
+    println!("Output:       {}", plan.output.display());
+    println!("Content Type: {:?}", plan.content_type);
+    println!("Encoder:      {}", encoder);
     println!("Violations:   {}", compliance_result.violations.len());
-
-    // Build optimized FFmpeg arguments
-    let mut args = vec!["-i".to_string(), input.to_str().unwrap().to_string()];
-
-    // Hardware acceleration settings
-    if should_use_hw_decode(compliance_result) {
-        args.extend(["-hwaccel".to_string(), "cuda".to_string()]);
+    println!("Method:       {}", plan.remux.method());
+    for (label, categories) in [
+        ("Lossless:  ", &plan.remux.lossless),
+        ("Re-encoded:", &plan.remux.re_encoded),
+        ("Unchanged: ", &plan.remux.unchanged),
+    ] {
+        if !categories.is_empty() {
+            println!("{}  {:?}", label, categories);
+        }
     }
 
-    // Content-aware video encoding
-    let video_fixes = generate_optimized_video_fixes(compliance_result, &content_type, metadata);
-    args.extend(video_fixes);
-
-    // Audio fixes
-    let audio_fixes = generate_audio_fixes(compliance_result);
-    args.extend(audio_fixes);
-
-    // Optimization flags
-    args.extend([
-        "-movflags".to_string(),
-        "+faststart".to_string(),
-        "-y".to_string(),
-        output_path.to_str().unwrap().to_string(),
-    ]);
-
-    println!("Optimized command: ffmpeg {}", args.join(" "));
+    println!("Optimized command: ffmpeg {}", plan.ffmpeg_args.join(" "));
 
     // Execute with progress tracking
-    execute_compliance_conversion(&args, compliance_result)?;
+    execute_compliance_conversion(&plan.ffmpeg_args, compliance_result)?;
 
-    Ok(output_path)
+    Ok(plan.output)
 }
 
-/// Generate optimized output filename including content type
-fn generate_optimized_output_filename(
+/// ffmpeg arguments for the optimized fixer, following the remux plan
+fn optimized_ffmpeg_args(
     input: &Path,
-    _compliance_result: &ComplianceResult,
-    _content_type: &ContentType,
-) -> String {
-    // Keep the original filename as requested by user
-    input.file_name().unwrap().to_str().unwrap().to_string()
-}
-
-/// Generate content-optimized video encoding arguments
-fn generate_optimized_video_fixes(
+    output: &Path,
     compliance_result: &ComplianceResult,
-    content_type: &ContentType,
-    _metadata: &VideoMetadata,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    encoder: EncoderBackend,
+    remux: &RemuxPlan,
 ) -> Vec<String> {
+    let content_type = detect_content_type(metadata, input);
     let mut args = Vec::new();
 
-    let needs_video_fix = compliance_result.violations.iter().any(|v| {
-        v.category == ViolationCategory::VideoCodec
-            || v.category == ViolationCategory::Resolution
-            || v.category == ViolationCategory::ColorSpace
-            || v.category == ViolationCategory::HDR
-    });
-
-    if needs_video_fix {
-        // Base H.264 NVENC settings
-        args.extend([
-            "-c:v".to_string(),
-            "h264_nvenc".to_string(),
-            "-profile:v".to_string(),
-            "high".to_string(),
-            "-pix_fmt".to_string(),
-            "yuv420p".to_string(),
-        ]);
-
-        // Content-specific optimizations
-        match content_type {
-            ContentType::ScreenCapture | ContentType::Presentation => {
-                // Screen capture: focus on sharpness and detail
-                args.extend([
-                    "-preset".to_string(),
-                    "p7".to_string(), // Highest quality
-                    "-cq".to_string(),
-                    "15".to_string(), // Very high quality
-                    "-temporal-aq".to_string(),
-                    "1".to_string(),
-                    "-rc-lookahead".to_string(),
-                    "32".to_string(),
-                ]);
-            }
-            ContentType::LiveAction => {
-                // Live action: balanced quality and efficiency
-                args.extend([
-                    "-preset".to_string(),
-                    "p5".to_string(), // Balanced preset
-                    "-cq".to_string(),
-                    "18".to_string(), // High quality
-                    "-spatial-aq".to_string(),
-                    "1".to_string(),
-                    "-temporal-aq".to_string(),
-                    "1".to_string(),
-                ]);
-            }
-            ContentType::Animation => {
-                // Animation: preserve flat colors and sharp edges
-                args.extend([
-                    "-preset".to_string(),
-                    "p6".to_string(),
-                    "-cq".to_string(),
-                    "16".to_string(),
-                    "-aq-mode".to_string(),
-                    "3".to_string(), // Temporal AQ
-                ]);
-            }
-            ContentType::Unknown => {
-                // Safe defaults
-                args.extend([
-                    "-preset".to_string(),
-                    "p6".to_string(),
-                    "-cq".to_string(),
-                    "18".to_string(),
-                ]);
-            }
-        }
+    // Hardware decoding only helps when the video is decoded at all (input options precede -i)
+    if remux.video == StreamAction::ReEncode && should_use_hw_decode(compliance_result) {
+        args.extend(encoder.hwaccel_args());
+    }
+    args.extend(["-i".to_string(), input.to_str().unwrap().to_string()]);
 
-        // Add resolution scaling if needed
-        if compliance_result
-            .violations
-            .iter()
-            .any(|v| v.category == ViolationCategory::Resolution)
-        {
-            if let Some(target_res) = determine_target_resolution(compliance_result) {
-                args.extend(["-vf".to_string(), format!("scale={}", target_res)]);
-            }
-        }
+    // Content-aware video encoding
+    args.extend(generate_optimized_video_fixes(
+        compliance_result,
+        &content_type,
+        metadata,
+        standards,
+        encoder,
+    ));
+
+    // Audio fixes, kept in sync with the video across a frame rate change
+    let audio_fixes =
+        generate_audio_fixes(compliance_result, metadata, standards, &remux.container);
+    let converts_frame_rate =
+        frame_rate_target(compliance_result, metadata, standards, &content_type).is_some();
+    args.extend(audio_sync_args(converts_frame_rate, &audio_fixes));
+    args.extend(audio_fixes);
 
-        // Add color space conversion if needed
-        if compliance_result.violations.iter().any(|v| {
-            v.category == ViolationCategory::ColorSpace || v.category == ViolationCategory::HDR
-        }) {
-            args.extend([
-                "-colorspace".to_string(),
-                "bt709".to_string(),
-                "-color_primaries".to_string(),
-                "bt709".to_string(),
-                "-color_trc".to_string(),
-                "bt709".to_string(),
-            ]);
-        }
-    } else {
-        args.extend(["-c:v".to_string(), "copy".to_string()]);
+    // Move the index to the front of QuickTime-family files for streaming
+    if remux.container == "mp4" || remux.container == "mov" {
+        args.extend(["-movflags".to_string(), "+faststart".to_string()]);
     }
-
+    args.extend(["-y".to_string(), output.to_str().unwrap().to_string()]);
     args
 }
 
-/// Batch processing for multiple files with progress tracking
-pub struct BatchProcessor {
-    pub total_files: usize,
-    pub processed_files: usize,
-    pub failed_files: Vec<(PathBuf, String)>,
-    pub fixed_files: Vec<PathBuf>,
-    pub skipped_files: Vec<PathBuf>,
+/// Output format for `--dry-run` plans
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlanFormat {
+    #[default]
+    Text,
+    Json,
 }
 
-impl BatchProcessor {
-    pub fn new(total_files: usize) -> Self {
-        Self {
-            total_files,
-            processed_files: 0,
-            failed_files: Vec::new(),
-            fixed_files: Vec::new(),
-            skipped_files: Vec::new(),
-        }
-    }
+/// One violation and what the fixer does about it
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedFix {
+    pub severity: ViolationSeverity,
+    pub category: ViolationCategory,
+    pub description: String,
+    pub current_value: String,
+    /// e.g. "scale to 1920x1080 (letterbox pad)" or "remux to MP4"
+    pub remedy: String,
+    pub lossy: bool,
+}
 
-    pub fn process_file_result(
-        &mut self,
-        path: PathBuf,
-        result: Result<Option<PathBuf>, VideoError>,
-    ) {
-        self.processed_files += 1;
+/// Streams and parameters of the file the fixer would write
+#[derive(Debug, Clone, Serialize)]
+pub struct FixTarget {
+    pub container: String,
+    /// Video encoder, or "copy"
+    pub video_codec: String,
+    pub resolution: Resolution,
+    pub frame_rate: f64,
+    /// Audio encoder, or "copy"
+    pub audio_codec: String,
+    pub audio_sample_rate: u32,
+}
 
-        match result {
-            Ok(Some(fixed_path)) => {
+/// The transformation the optimized fixer would apply to one file: each
+/// violation with its remedy, the target parameters, an output size estimate
+/// and the ffmpeg command. Building a plan touches no files.
+#[derive(Debug, Clone, Serialize)]
+pub struct FixPlan {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub content_type: ContentType,
+    pub remux: RemuxPlan,
+    pub fixes: Vec<PlannedFix>,
+    pub target: FixTarget,
+    /// Estimated output size in bytes
+    pub estimated_size: u64,
+    /// True when any stream is re-encoded with a lossy codec
+    pub lossy: bool,
+    pub ffmpeg_args: Vec<String>,
+}
+
+impl FixPlan {
+    pub fn new(
+        input: &Path,
+        output_dir: &Path,
+        compliance_result: &ComplianceResult,
+        metadata: &VideoMetadata,
+        standards: &ContentStandards,
+        encoder: EncoderBackend,
+    ) -> Self {
+        let content_type = detect_content_type(metadata, input);
+        let remux = plan_remux(compliance_result, metadata, standards, &content_type);
+        let output = output_dir.join(generate_optimized_output_filename(input, &remux.container));
+        let ffmpeg_args = optimized_ffmpeg_args(
+            input,
+            &output,
+            compliance_result,
+            metadata,
+            standards,
+            encoder,
+            &remux,
+        );
+
+        // Read the chosen encoders and audio settings back from the arguments
+        let arg_value = |flag: &str| {
+            ffmpeg_args
+                .windows(2)
+                .find(|pair| pair[0] == flag)
+                .map(|pair| pair[1].clone())
+        };
+        let audio_codec = arg_value("-c:a").unwrap_or_else(|| "copy".to_string());
+        let audio_sample_rate = arg_value("-ar")
+            .and_then(|rate| rate.parse().ok())
+            .unwrap_or(metadata.audio_sample_rate);
+
+        let video_reencoded = remux.video == StreamAction::ReEncode;
+        let resolution = if !video_reencoded {
+            metadata.effective_resolution()
+        } else if remux.re_encoded.contains(&ViolationCategory::Resolution) {
+            determine_target_resolution(metadata, standards)
+                .unwrap_or_else(|| metadata.display_resolution())
+        } else {
+            metadata.display_resolution()
+        };
+        let target = FixTarget {
+            container: remux.container.clone(),
+            video_codec: arg_value("-c:v").unwrap_or_else(|| "copy".to_string()),
+            resolution,
+            frame_rate: frame_rate_target(compliance_result, metadata, standards, &content_type)
+                .unwrap_or(metadata.fps),
+            audio_codec,
+            audio_sample_rate,
+        };
+
+        let fixes = compliance_result
+            .violations
+            .iter()
+            .map(|violation| {
+                let (remedy, lossy) =
+                    planned_remedy(violation, metadata, standards, &remux, &target);
+                PlannedFix {
+                    severity: violation.severity.clone(),
+                    category: violation.category.clone(),
+                    description: violation.description.clone(),
+                    current_value: violation.current_value.clone(),
+                    remedy,
+                    lossy,
+                }
+            })
+            .collect();
+
+        FixPlan {
+            input: input.to_path_buf(),
+            output,
+            estimated_size: estimate_output_size(metadata, &content_type, &target),
+            lossy: video_reencoded || target.audio_codec == "aac",
+            content_type,
+            remux,
+            fixes,
+            target,
+            ffmpeg_args,
+        }
+    }
+
+    pub fn display(&self) {
+        println!(
+            "\n{} {}",
+            "📝 Fix Plan:".bright_blue().bold(),
+            self.input.display()
+        );
+        println!("  {} {}", "Output:".blue(), self.output.display());
+        println!(
+            "  {} {}{}",
+            "Method:".blue(),
+            self.remux.method(),
+            if self.lossy {
+                " (lossy)"
+            } else {
+                " (lossless)"
+            }
+        );
+        println!(
+            "  {} {} {} @ {} fps in {}",
+            "Video:".blue(),
+            self.target.video_codec,
+            self.target.resolution,
+            self.target.frame_rate,
+            self.target.container.to_uppercase()
+        );
+        if self.target.audio_codec == "copy" {
+            println!("  {} copy", "Audio:".blue());
+        } else {
+            println!(
+                "  {} {} at {} Hz",
+                "Audio:".blue(),
+                self.target.audio_codec,
+                self.target.audio_sample_rate
+            );
+        }
+        println!(
+            "  {} {}",
+            "Estimated Size:".blue(),
+            format_size(self.estimated_size, DECIMAL)
+        );
+        for fix in &self.fixes {
+            let icon = match fix.severity {
+                ViolationSeverity::Critical => "🔴",
+                ViolationSeverity::Warning => "🟡",
+                ViolationSeverity::Info => "🔵",
+            };
+            println!(
+                "  {} {:?}: {} ({}) -> {}{}",
+                icon,
+                fix.category,
+                fix.description,
+                fix.current_value,
+                fix.remedy,
+                if fix.lossy { " [lossy]" } else { "" }
+            );
+        }
+        println!(
+            "  {} ffmpeg {}",
+            "Command:".blue(),
+            self.ffmpeg_args.join(" ")
+        );
+    }
+}
+
+/// Remedy for one violation under the plan, and whether it loses quality
+fn planned_remedy(
+    violation: &ComplianceViolation,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    remux: &RemuxPlan,
+    target: &FixTarget,
+) -> (String, bool) {
+    if remux.unchanged.contains(&violation.category) {
+        return ("left unchanged".to_string(), false);
+    }
+
+    // Intermediate codecs are re-encoded within their own format rules
+    let format_rule = matches!(
+        violation.category,
+        ViolationCategory::VideoCodec
+            | ViolationCategory::Profile
+            | ViolationCategory::ChromaSubsampling
+            | ViolationCategory::BitDepth
+    );
+    if let Some(format) = standards.codec_format(&metadata.codec) {
+        let args = intermediate_encode_args(&metadata.codec, format, metadata);
+        if let Some(args) = args.filter(|_| format_rule && remux.video == StreamAction::ReEncode) {
+            let remedy = format!(
+                "re-encode as {} ({}, {})",
+                format.name, target.video_codec, args[5]
+            );
+            return (remedy, true);
+        }
+    }
+
+    let remedy = match violation.category {
+        ViolationCategory::Container => {
+            return (
+                format!("remux to {}", target.container.to_uppercase()),
+                false,
+            )
+        }
+        ViolationCategory::Faststart => {
+            return (
+                "move the index to the front (+faststart)".to_string(),
+                false,
+            )
+        }
+        ViolationCategory::ColorSpace => match &remux.bitstream_filter {
+            Some(_) if remux.video == StreamAction::Copy => {
+                return ("tag as Rec. 709 in the bitstream".to_string(), false)
+            }
+            _ => "tag as Rec. 709 while re-encoding".to_string(),
+        },
+        ViolationCategory::Audio | ViolationCategory::AudioCodec => {
+            let lossy = target.audio_codec == "aac";
+            return (
+                format!(
+                    "re-encode audio to {} at {} Hz",
+                    target.audio_codec, target.audio_sample_rate
+                ),
+                lossy,
+            );
+        }
+        ViolationCategory::VideoCodec | ViolationCategory::Profile => {
+            format!("re-encode to H.264 high profile ({})", target.video_codec)
+        }
+        ViolationCategory::Resolution => format!(
+            "scale to {} ({})",
+            target.resolution, standards.video.scaling_policy
+        ),
+        ViolationCategory::AspectRatio => "resample to square pixels".to_string(),
+        ViolationCategory::Rotation => {
+            format!("rotate {}° into the pixels", metadata.rotation)
+        }
+        ViolationCategory::Interlacing => {
+            format!("deinterlace ({})", metadata.interlaced_field_order())
+        }
+        ViolationCategory::FrameRate => format!("convert to {} fps", target.frame_rate),
+        ViolationCategory::HDR if metadata.hdr_format().is_hdr() => format!(
+            "tone map to SDR Rec. 709 ({})",
+            standards.quality.tone_mapping
+        ),
+        ViolationCategory::HDR => "convert primaries to BT.709".to_string(),
+        ViolationCategory::Keyframe => format!(
+            "re-encode with keyframes every {} s",
+            standards.quality.keyframe_interval_min
+        ),
+        ViolationCategory::ChromaSubsampling | ViolationCategory::BitDepth => {
+            "convert to 8-bit 4:2:0 (yuv420p)".to_string()
+        }
+        ViolationCategory::Bitrate => "re-encode at constant quality".to_string(),
+    };
+    (remedy, true)
+}
+
+/// Rough output size from the target bitrates: copied streams and
+/// intermediate codecs keep their bitrate, re-encoded H.264 gets the content
+/// type's typical bitrate
+fn estimate_output_size(
+    metadata: &VideoMetadata,
+    content_type: &ContentType,
+    target: &FixTarget,
+) -> u64 {
+    let video_bps = if matches!(target.video_codec.as_str(), "copy" | "prores_ks" | "dnxhd") {
+        metadata.effective_video_bitrate().unwrap_or(0)
+    } else {
+        get_optimal_bitrate(content_type, target.resolution) as u64 * 1000
+    };
+
+    let pcm_bps = |bits: u64| target.audio_sample_rate as u64 * bits * 2;
+    let lossless_bits = if metadata.audio_bit_depth == 16 {
+        16
+    } else {
+        24
+    };
+    let audio_bps = match target.audio_codec.as_str() {
+        "copy" => metadata.audio_bitrate,
+        "pcm_s16le" => pcm_bps(16),
+        "pcm_s24le" => pcm_bps(24),
+        // ALAC typically compresses to about 60% of PCM
+        "alac" => pcm_bps(lossless_bits) * 6 / 10,
+        _ => 320_000,
+    };
+
+    ((video_bps + audio_bps) as f64 * metadata.duration / 8.0) as u64
+}
+
+/// Output filename: the original name, with the extension of the output container
+fn generate_optimized_output_filename(input: &Path, container: &str) -> String {
+    let file_name = input.file_name().unwrap().to_str().unwrap().to_string();
+    let same_container = input
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(container));
+    if same_container {
+        file_name
+    } else {
+        format!(
+            "{}.{}",
+            input.file_stem().unwrap().to_str().unwrap(),
+            container
+        )
+    }
+}
+
+/// Recommendation suffix for intermediate codecs the fixer cannot write back
+/// in their own format, and therefore leaves alone
+fn manual_fix_note(metadata: &VideoMetadata, format: &CodecFormat) -> &'static str {
+    match intermediate_encode_args(&metadata.codec, format, metadata) {
+        Some(_) => "",
+        None => " (not fixed automatically: no ffmpeg encoder for this format)",
+    }
+}
+
+/// Encoder arguments that keep an intermediate codec in its own format, e.g.
+/// `-c:v prores_ks -profile:v 3 -pix_fmt yuv422p10le` for ProRes 422 HQ.
+/// The source profile, chroma and bit depth are kept when the format allows
+/// them, otherwise the first allowed value is used. `None` when ffmpeg has no
+/// encoder for the codec or none of the allowed profiles can be encoded.
+fn intermediate_encode_args(
+    codec: &str,
+    format: &CodecFormat,
+    metadata: &VideoMetadata,
+) -> Option<Vec<String>> {
+    let mut profiles = std::iter::once(metadata.profile.as_str())
+        .filter(|profile| format.profiles.iter().any(|p| p == profile))
+        .chain(format.profiles.iter().map(String::as_str));
+
+    let (encoder, profile) = match codec {
+        "prores" => {
+            // prores_ks profile numbers, by ffprobe profile name
+            let profile = profiles
+                .filter_map(|profile| match profile {
+                    "proxy" => Some("0"),
+                    "lt" => Some("1"),
+                    "standard" => Some("2"),
+                    "hq" => Some("3"),
+                    "4444" => Some("4"),
+                    "4444 xq" | "4444xq" => Some("5"),
+                    _ => None,
+                })
+                .next()
+                .or(format.profiles.is_empty().then_some("2"))?;
+            ("prores_ks", profile.to_string())
+        }
+        "dnxhd" => {
+            // DNxHR profiles work at any resolution; classic DNxHD needs a fixed bitrate
+            let profile = profiles
+                .find(|profile| profile.starts_with("dnxhr "))
+                .map(|profile| profile.replace(' ', "_"))
+                .or(format.profiles.is_empty().then(|| "dnxhr_sq".to_string()))?;
+            ("dnxhd", profile)
+        }
+        _ => return None,
+    };
+
+    let chroma = if format
+        .chroma_subsampling
+        .contains(&metadata.chroma_subsampling)
+    {
+        metadata.chroma_subsampling.as_str()
+    } else {
+        format.chroma_subsampling.first()?.as_str()
+    };
+    let bit_depth = if format.bit_depths.contains(&metadata.bit_depth) {
+        metadata.bit_depth
+    } else {
+        *format.bit_depths.first()?
+    };
+    let pix_fmt = format!(
+        "yuv{}p{}",
+        chroma.replace(':', ""),
+        if bit_depth > 8 {
+            format!("{}le", bit_depth)
+        } else {
+            String::new()
+        }
+    );
+
+    Some(vec![
+        "-c:v".to_string(),
+        encoder.to_string(),
+        "-profile:v".to_string(),
+        profile,
+        "-pix_fmt".to_string(),
+        pix_fmt,
+    ])
+}
+
+/// Generate content-optimized video encoding arguments
+fn generate_optimized_video_fixes(
+    compliance_result: &ComplianceResult,
+    content_type: &ContentType,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    encoder: EncoderBackend,
+) -> Vec<String> {
+    let mut args = Vec::new();
+
+    // Variable or out-of-spec frame rates are converted to an allowed constant rate
+    let frame_rate = frame_rate_target(compliance_result, metadata, standards, content_type);
+
+    if video_needs_reencode(compliance_result, metadata, standards, content_type) {
+        // Intermediate codecs are re-encoded in their own format; they are
+        // intra-frame, so there is no keyframe interval to set
+        let intermediate = standards
+            .codec_format(&metadata.codec)
+            .and_then(|format| intermediate_encode_args(&metadata.codec, format, metadata));
+        if let Some(intermediate) = intermediate {
+            args.extend(intermediate);
+        } else {
+            // Base H.264 settings
+            args.extend([
+                "-c:v".to_string(),
+                encoder.ffmpeg_name().to_string(),
+                "-profile:v".to_string(),
+                "high".to_string(),
+                "-pix_fmt".to_string(),
+                "yuv420p".to_string(),
+            ]);
+
+            // Content-specific optimizations
+            args.extend(encoder.content_args(content_type));
+
+            // Keep keyframes at least the minimum interval apart
+            args.extend(keyframe_interval_args(
+                frame_rate.unwrap_or(metadata.fps),
+                standards.quality.keyframe_interval_min,
+            ));
+        }
+
+        // Deinterlace before any other filter; rotated sources arrive upright
+        // from ffmpeg's autorotate
+        let mut filters = Vec::new();
+        if compliance_result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::Interlacing)
+        {
+            filters.push(interlace::deinterlace_filter(
+                metadata.interlaced_field_order(),
+            ));
+        }
+
+        filters.extend(frame_rate.map(|rate| frame_rate_filter(metadata, rate, standards)));
+
+        // Tone map or convert the gamut before scaling, so scaling works on SDR pixels
+        filters.extend(color_conversion_filters(
+            compliance_result,
+            metadata,
+            standards,
+        ));
+
+        // Resample anamorphic video to square pixels, keeping the display aspect
+        if !metadata.has_square_pixels() {
+            filters.push("scale=trunc(iw*sar/2)*2:ih".to_string());
+            filters.push("setsar=1".to_string());
+        }
+
+        // Add resolution scaling if needed
+        if compliance_result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::Resolution)
+        {
+            filters.extend(resolution_filters(metadata, standards));
+        }
+
+        if !filters.is_empty() {
+            args.extend(["-vf".to_string(), filters.join(",")]);
+        }
+
+        // Tag the output as Rec. 709, including untagged sources
+        if compliance_result.violations.iter().any(|v| {
+            v.category == ViolationCategory::ColorSpace || v.category == ViolationCategory::HDR
+        }) {
+            args.extend([
+                "-colorspace".to_string(),
+                "bt709".to_string(),
+                "-color_primaries".to_string(),
+                "bt709".to_string(),
+                "-color_trc".to_string(),
+                "bt709".to_string(),
+            ]);
+        }
+    } else {
+        args.extend(["-c:v".to_string(), "copy".to_string()]);
+        if let Some(bsf) = colour_tag_bitstream_filter(compliance_result, metadata) {
+            args.extend(["-bsf:v".to_string(), bsf]);
+        }
+    }
+
+    args
+}
+
+/// True when the optimized fixer has to decode and re-encode the video stream.
+/// Intermediate codecs ffmpeg cannot write back in their own format are left
+/// alone rather than turned into H.264.
+fn video_needs_reencode(
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    content_type: &ContentType,
+) -> bool {
+    if standards
+        .codec_format(&metadata.codec)
+        .is_some_and(|format| intermediate_encode_args(&metadata.codec, format, metadata).is_none())
+    {
+        return false;
+    }
+    compliance_result.violations.iter().any(|v| {
+        matches!(
+            v.category,
+            ViolationCategory::VideoCodec
+                | ViolationCategory::Profile
+                | ViolationCategory::Keyframe
+                | ViolationCategory::AspectRatio
+                | ViolationCategory::Rotation
+                | ViolationCategory::Interlacing
+                | ViolationCategory::ChromaSubsampling
+                | ViolationCategory::BitDepth
+                | ViolationCategory::Resolution
+                | ViolationCategory::HDR
+        )
+    }) || frame_rate_target(compliance_result, metadata, standards, content_type).is_some()
+}
+
+/// Bitstream filter that writes Rec. 709 colour tags into the H.264/HEVC
+/// sequence header of a copied stream flagged as untagged
+fn colour_tag_bitstream_filter(
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+) -> Option<String> {
+    if !compliance_result
+        .violations
+        .iter()
+        .any(|v| v.category == ViolationCategory::ColorSpace)
+    {
+        return None;
+    }
+    let filter = match metadata.codec.as_str() {
+        "h264" => "h264_metadata",
+        "hevc" => "hevc_metadata",
+        _ => return None,
+    };
+    // 1 is BT.709 for all three in H.273
+    Some(format!(
+        "{}=colour_primaries=1:transfer_characteristics=1:matrix_coefficients=1",
+        filter
+    ))
+}
+
+/// Container the fixed file is written in: the source container unless it was
+/// flagged, then the first allowed one (QuickTime when copied PCM audio rules out MP4)
+fn output_container(
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+) -> String {
+    let source = metadata.container.to_lowercase();
+    if !compliance_result
+        .violations
+        .iter()
+        .any(|v| v.category == ViolationCategory::Container)
+    {
+        return source;
+    }
+
+    let containers = match standards.codec_format(&metadata.codec) {
+        Some(format) => &format.containers,
+        None => &standards.video.containers,
+    };
+    let copies_pcm = audio_codec_family(&metadata.audio_codec) == "pcm"
+        && !audio_needs_reencode(compliance_result);
+    match containers.first().map(String::as_str) {
+        Some("mp4") if copies_pcm && containers.iter().any(|c| c == "mov") => "mov".to_string(),
+        Some(container) => container.to_string(),
+        None => source,
+    }
+}
+
+/// Whether a stream of the fixed file is copied bit for bit or re-encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamAction {
+    Copy,
+    ReEncode,
+}
+
+/// How the fixer produces a file: which streams are re-encoded, the output
+/// container, and which violations that fixes without loss. A plan that
+/// copies both streams is a lossless remux.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemuxPlan {
+    pub video: StreamAction,
+    pub audio: StreamAction,
+    /// Output container (file extension)
+    pub container: String,
+    /// `-bsf:v` filter correcting tags on a copied video stream
+    pub bitstream_filter: Option<String>,
+    /// Violations fixed by stream copy (container change, tag corrections)
+    pub lossless: Vec<ViolationCategory>,
+    /// Violations fixed by re-encoding a stream
+    pub re_encoded: Vec<ViolationCategory>,
+    /// Violations the fix leaves as they are, e.g. bitrate on a copied stream
+    pub unchanged: Vec<ViolationCategory>,
+}
+
+impl RemuxPlan {
+    /// Nothing is decoded or re-encoded
+    pub fn is_lossless(&self) -> bool {
+        self.video == StreamAction::Copy && self.audio == StreamAction::Copy
+    }
+
+    /// One-line description, e.g. "lossless remux to MP4"
+    pub fn method(&self) -> String {
+        match (self.video, self.audio) {
+            (StreamAction::Copy, StreamAction::Copy) => {
+                format!("lossless remux to {}", self.container.to_uppercase())
+            }
+            (StreamAction::Copy, StreamAction::ReEncode) => {
+                "copy video, re-encode audio".to_string()
+            }
+            (StreamAction::ReEncode, StreamAction::Copy) => {
+                "re-encode video, copy audio".to_string()
+            }
+            (StreamAction::ReEncode, StreamAction::ReEncode) => {
+                "re-encode video and audio".to_string()
+            }
+        }
+    }
+}
+
+/// Work out which violations a stream copy can fix and which need a re-encode
+pub fn plan_remux(
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    content_type: &ContentType,
+) -> RemuxPlan {
+    let video = if video_needs_reencode(compliance_result, metadata, standards, content_type) {
+        StreamAction::ReEncode
+    } else {
+        StreamAction::Copy
+    };
+    let audio = if audio_needs_reencode(compliance_result) {
+        StreamAction::ReEncode
+    } else {
+        StreamAction::Copy
+    };
+    let bitstream_filter = match video {
+        StreamAction::Copy => colour_tag_bitstream_filter(compliance_result, metadata),
+        StreamAction::ReEncode => None,
+    };
+
+    let mut plan = RemuxPlan {
+        video,
+        audio,
+        container: output_container(compliance_result, metadata, standards),
+        bitstream_filter,
+        lossless: Vec::new(),
+        re_encoded: Vec::new(),
+        unchanged: Vec::new(),
+    };
+
+    for violation in &compliance_result.violations {
+        let category = violation.category.clone();
+        let action = match category {
+            ViolationCategory::Container | ViolationCategory::Faststart => Some(StreamAction::Copy),
+            // Tags are set by the bitstream filter, or by the encoder on re-encode
+            ViolationCategory::ColorSpace if video == StreamAction::ReEncode => {
+                Some(StreamAction::ReEncode)
+            }
+            ViolationCategory::ColorSpace => {
+                plan.bitstream_filter.as_ref().map(|_| StreamAction::Copy)
+            }
+            ViolationCategory::Audio | ViolationCategory::AudioCodec => {
+                (audio == StreamAction::ReEncode).then_some(StreamAction::ReEncode)
+            }
+            _ => (video == StreamAction::ReEncode).then_some(StreamAction::ReEncode),
+        };
+        let list = match action {
+            Some(StreamAction::Copy) => &mut plan.lossless,
+            Some(StreamAction::ReEncode) => &mut plan.re_encoded,
+            None => &mut plan.unchanged,
+        };
+        if !list.contains(&category) {
+            list.push(category);
+        }
+    }
+    plan
+}
+
+/// GOP size arguments for a keyframe every `interval_seconds` at the given frame rate
+fn keyframe_interval_args(fps: f64, interval_seconds: u32) -> Vec<String> {
+    if fps <= 0.0 || interval_seconds == 0 {
+        return Vec::new();
+    }
+
+    let gop_frames = (fps * interval_seconds as f64).round() as u32;
+    vec![
+        "-g".to_string(),
+        gop_frames.to_string(),
+        "-keyint_min".to_string(),
+        gop_frames.to_string(),
+    ]
+}
+
+/// Batch processing for multiple files with progress tracking
+pub struct BatchProcessor {
+    pub total_files: usize,
+    pub processed_files: usize,
+    pub failed_files: Vec<(PathBuf, String)>,
+    pub fixed_files: Vec<PathBuf>,
+    pub skipped_files: Vec<PathBuf>,
+}
+
+impl BatchProcessor {
+    pub fn new(total_files: usize) -> Self {
+        Self {
+            total_files,
+            processed_files: 0,
+            failed_files: Vec::new(),
+            fixed_files: Vec::new(),
+            skipped_files: Vec::new(),
+        }
+    }
+
+    pub fn process_file_result(
+        &mut self,
+        path: PathBuf,
+        result: Result<Option<PathBuf>, VideoError>,
+    ) {
+        self.processed_files += 1;
+
+        match result {
+            Ok(Some(fixed_path)) => {
                 self.fixed_files.push(fixed_path);
             }
             Ok(None) => {
@@ -1455,6 +4091,7 @@ pub fn process_single_file_batch(
     path: &Path,
     output_dir: &Path,
     compliance_engine: &ComplianceEngine,
+    encoder: EncoderBackend,
     verbose: bool,
 ) -> Result<Option<PathBuf>, VideoError> {
     if verbose {
@@ -1475,8 +4112,14 @@ pub fn process_single_file_batch(
     }
 
     // Fix the non-compliant file
-    let fixed_path =
-        fix_video_compliance_optimized(path, output_dir, &compliance_result, &metadata)?;
+    let fixed_path = fix_video_compliance_optimized(
+        path,
+        output_dir,
+        &compliance_result,
+        &metadata,
+        compliance_engine.get_standards(),
+        encoder,
+    )?;
 
     if verbose {
         println!("✅ Fixed: {} -> {}", path.display(), fixed_path.display());
@@ -1485,6 +4128,11 @@ pub fn process_single_file_batch(
     Ok(Some(fixed_path))
 }
 
+/// File extensions picked up by `validate_directory`; .mov and .mxf carry
+/// ProRes and DNxHD deliveries, while .mkv, .m4v and .avi files are usually
+/// fixed by remuxing to MP4 or MOV
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "avi", "mkv", "mov", "mxf"];
+
 pub fn validate_directory(dir: &Path) -> Result<Vec<PathBuf>, VideoError> {
     if !dir.is_dir() {
         return Err(VideoError::InvalidPath(format!(
@@ -1499,7 +4147,11 @@ pub fn validate_directory(dir: &Path) -> Result<Vec<PathBuf>, VideoError> {
             e.path()
                 .extension()
                 .and_then(|ext| ext.to_str())
-                .map(|ext| ext == "mp4" || ext == "avi")
+                .map(|ext| {
+                    VIDEO_EXTENSIONS
+                        .iter()
+                        .any(|known| ext.eq_ignore_ascii_case(known))
+                })
                 .unwrap_or(false)
         })
         .map(|e| e.path())
@@ -1511,14 +4163,70 @@ pub fn validate_directory(dir: &Path) -> Result<Vec<PathBuf>, VideoError> {
 
     Ok(entries)
 }
+/// Options for a `process_directory_with_options` run
+#[derive(Debug, Default)]
+pub struct ProcessOptions {
+    pub convert: bool,
+    pub verbose: bool,
+    pub compliance: bool,
+    /// Standards to enforce; the bundled defaults are used when `None`
+    pub standards: Option<ContentStandards>,
+    /// Encoder backend used for conversion
+    pub encoder: EncoderPreference,
+    /// Decode frames through `idet` to detect interlacing the stream does not signal
+    pub detect_interlace: bool,
+    /// Print the fix plan for each file instead of converting; no files are written
+    pub dry_run: Option<PlanFormat>,
+}
+
 pub fn process_directory(
     dir: &Path,
     should_convert: bool,
     verbose: bool,
     analyze_compliance: bool,
 ) -> Result<(), VideoError> {
+    let options = ProcessOptions {
+        convert: should_convert,
+        verbose,
+        compliance: analyze_compliance,
+        ..Default::default()
+    };
+    process_directory_with_options(dir, options)
+}
+
+pub fn process_directory_with_options(
+    dir: &Path,
+    options: ProcessOptions,
+) -> Result<(), VideoError> {
+    let ProcessOptions {
+        convert: should_convert,
+        verbose,
+        compliance: analyze_compliance,
+        standards,
+        encoder,
+        detect_interlace,
+        dry_run,
+    } = options;
+
     let video_files = validate_directory(dir)?;
-    check_hardware_support()?;
+    // Encoder and GPU probing is deferred until a file actually needs converting,
+    // so analysis-only runs work on machines without NVENC
+    let mut encoder = LazyEncoder::new(encoder);
+
+    if let Some(format) = dry_run {
+        let engine = match standards {
+            Some(standards) => ComplianceEngine::with_standards(standards),
+            None => ComplianceEngine::new()?,
+        };
+        return plan_directory(
+            dir,
+            video_files,
+            &engine,
+            &mut encoder,
+            detect_interlace,
+            format,
+        );
+    }
 
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(
@@ -1536,7 +4244,10 @@ pub fn process_directory(
     };
 
     let compliance_engine = if analyze_compliance {
-        Some(ComplianceEngine::new()?)
+        Some(match standards {
+            Some(standards) => ComplianceEngine::with_standards(standards),
+            None => ComplianceEngine::new()?,
+        })
     } else {
         None
     };
@@ -1550,7 +4261,13 @@ pub fn process_directory(
     let mut compliance_summary = ComplianceSummary::new();
 
     for path in video_files {
-        let metadata = analyze_video(&path)?;
+        let mut metadata = analyze_video(&path)?;
+        if detect_interlace {
+            match interlace::detect_interlacing(&path, interlace::IDET_FRAMES) {
+                Ok(analysis) => metadata.interlace = Some(analysis),
+                Err(e) => warn!(?e, "idet analysis failed"),
+            }
+        }
         println!(
             "\n{}",
             format!("File: {}", path.file_name().unwrap().to_str().unwrap()).bold()
@@ -1563,22 +4280,99 @@ pub fn process_directory(
 
         if verbose {
             println!("  {} {}", "Codec:".blue(), metadata.codec);
-            println!("  {} {}", "Resolution:".blue(), metadata.resolution);
+            let mut resolution = metadata.effective_resolution().to_string();
+            if metadata.rotation != 0 {
+                resolution.push_str(&format!(" (rotated {}°)", metadata.rotation));
+            }
+            if metadata.has_square_pixels() {
+                println!("  {} {}", "Resolution:".blue(), resolution);
+            } else {
+                println!(
+                    "  {} {} (SAR {}, DAR {})",
+                    "Resolution:".blue(),
+                    resolution,
+                    metadata.sample_aspect_ratio,
+                    metadata.display_aspect_ratio_name()
+                );
+            }
             println!(
                 "  {} {} Mbps",
                 "Bitrate:".blue(),
                 metadata.bitrate as f64 / 1_000_000.0
             );
+            if let Some(video_bitrate) = metadata.effective_video_bitrate() {
+                println!(
+                    "  {} {} Mbps",
+                    "Video Bitrate:".blue(),
+                    video_bitrate as f64 / 1_000_000.0
+                );
+            }
             println!(
                 "  {} {}",
                 "Size:".blue(),
                 format_size(metadata.size, DECIMAL)
             );
             println!("  {} {:.1} fps", "Frame Rate:".blue(), metadata.fps);
-            println!("  {} {}", "Audio:".blue(), metadata.audio_codec);
+            if metadata.audio_channels > 0 {
+                let bit_depth = if metadata.audio_bit_depth > 0 {
+                    format!(", {}-bit", metadata.audio_bit_depth)
+                } else {
+                    String::new()
+                };
+                println!(
+                    "  {} {} ({} Hz{}, {})",
+                    "Audio:".blue(),
+                    metadata.audio_codec,
+                    metadata.audio_sample_rate,
+                    bit_depth,
+                    channel_layout_name(metadata.audio_channels, &metadata.audio_channel_layout)
+                );
+            } else {
+                println!("  {} {}", "Audio:".blue(), metadata.audio_codec);
+            }
             println!("  {} {}", "Container:".blue(), metadata.container);
-            println!("  {} {}", "Profile:".blue(), metadata.profile);
+            if metadata.level > 0 {
+                println!(
+                    "  {} {} (level {})",
+                    "Profile:".blue(),
+                    metadata.profile,
+                    format_h264_level(metadata.level)
+                );
+            } else {
+                println!("  {} {}", "Profile:".blue(), metadata.profile);
+            }
             println!("  {} {}", "Color Space:".blue(), metadata.color_space);
+            if metadata.hdr_format().is_hdr() {
+                println!("  {} {}", "HDR:".blue(), metadata.hdr_summary());
+            }
+            if metadata.bit_depth > 0 {
+                println!(
+                    "  {} {} ({}, {}-bit)",
+                    "Pixel Format:".blue(),
+                    metadata.pix_fmt,
+                    metadata.chroma_subsampling,
+                    metadata.bit_depth
+                );
+            }
+            if let Some(audio_packets) = &metadata.audio_packets {
+                println!("  {} {}", "Audio Bitrate:".blue(), audio_packets.summary());
+            }
+            if let Some(gop) = &metadata.gop {
+                println!("  {} {}", "Keyframe Interval:".blue(), gop.summary());
+            }
+            if let Some(timing) = &metadata.frame_timing {
+                println!("  {} {}", "Frame Timing:".blue(), timing.summary());
+            }
+            if let Some(interlace) = &metadata.interlace {
+                println!(
+                    "  {} {} ({})",
+                    "Field Order:".blue(),
+                    metadata.interlaced_field_order(),
+                    interlace.summary()
+                );
+            } else if metadata.field_order != FieldOrder::Unknown {
+                println!("  {} {}", "Field Order:".blue(), metadata.field_order);
+            }
         }
 
         // Run compliance analysis if requested - moved outside to fix scope
@@ -1596,11 +4390,17 @@ pub fn process_directory(
         // Intelligent compliance-driven fixing
         if let Some(ref h264_dir) = h264_dir {
             if analyze_compliance {
-                if let Some(ref result) = compliance_result {
+                if let (Some(result), Some(engine)) = (&compliance_result, &compliance_engine) {
                     if !result.is_compliant {
                         // Use content-aware intelligent compliance fixing
-                        let fixed_path =
-                            fix_video_compliance_optimized(&path, h264_dir, result, &metadata)?;
+                        let fixed_path = fix_video_compliance_optimized(
+                            &path,
+                            h264_dir,
+                            result,
+                            &metadata,
+                            engine.get_standards(),
+                            encoder.get()?,
+                        )?;
                         println!(
                             "{} Fixed file saved: {}",
                             "✅".green(),
@@ -1615,7 +4415,7 @@ pub fn process_directory(
                 }
             } else {
                 // Legacy basic conversion for backward compatibility
-                convert_video(&path, h264_dir)?;
+                convert_video(&path, h264_dir, encoder.get()?)?;
             }
         }
     }
@@ -1633,6 +4433,100 @@ pub fn process_directory(
     Ok(())
 }
 
+/// Dry run: analyze every file and print the fix plans for non-compliant ones.
+/// Nothing is converted and the H264 directory is not created.
+fn plan_directory(
+    dir: &Path,
+    video_files: Vec<PathBuf>,
+    engine: &ComplianceEngine,
+    encoder: &mut LazyEncoder,
+    detect_interlace: bool,
+    format: PlanFormat,
+) -> Result<(), VideoError> {
+    let output_dir = dir.join("H264");
+    let mut plans = Vec::new();
+
+    for path in video_files {
+        let mut metadata = analyze_video(&path)?;
+        if detect_interlace {
+            match interlace::detect_interlacing(&path, interlace::IDET_FRAMES) {
+                Ok(analysis) => metadata.interlace = Some(analysis),
+                Err(e) => warn!(?e, "idet analysis failed"),
+            }
+        }
+
+        let result = engine.analyze_compliance(&metadata);
+        if result.is_compliant {
+            if format == PlanFormat::Text {
+                println!(
+                    "\n{} {}: already compliant, no fixing needed",
+                    "✅".green(),
+                    path.display()
+                );
+            }
+            continue;
+        }
+
+        let plan = plan_file(
+            &path,
+            &output_dir,
+            &result,
+            &metadata,
+            engine.get_standards(),
+            encoder,
+        );
+        match format {
+            PlanFormat::Text => plan.display(),
+            PlanFormat::Json => plans.push(plan),
+        }
+    }
+
+    if format == PlanFormat::Json {
+        let json = serde_json::to_string_pretty(&plans)
+            .map_err(|e| VideoError::Compliance(e.to_string()))?;
+        println!("{}", json);
+    }
+    Ok(())
+}
+
+/// Fix plan for one file. The encoder is only probed when the plan re-encodes
+/// to H.264; when no encoder is usable the plan is still built, with the
+/// target codec marked as unavailable.
+fn plan_file(
+    input: &Path,
+    output_dir: &Path,
+    compliance_result: &ComplianceResult,
+    metadata: &VideoMetadata,
+    standards: &ContentStandards,
+    encoder: &mut LazyEncoder,
+) -> FixPlan {
+    let provisional = encoder.provisional();
+    let plan = |backend| {
+        FixPlan::new(
+            input,
+            output_dir,
+            compliance_result,
+            metadata,
+            standards,
+            backend,
+        )
+    };
+    let mut fix_plan = plan(provisional);
+    if fix_plan.target.video_codec != provisional.ffmpeg_name() {
+        return fix_plan;
+    }
+
+    match encoder.get() {
+        Ok(backend) if backend != provisional => fix_plan = plan(backend),
+        Ok(_) => {}
+        Err(e) => {
+            warn!(?e, "no usable encoder, planning with {}", provisional);
+            fix_plan.target.video_codec = format!("{} (unavailable)", provisional);
+        }
+    }
+    fix_plan
+}
+
 #[derive(Debug, Default)]
 pub struct ComplianceSummary {
     pub total_files: usize,
@@ -1859,9 +4753,10 @@ mod tests {
     fn create_test_metadata() -> VideoMetadata {
         VideoMetadata {
             codec: "h264".to_string(),
-            resolution: "1920x1080".to_string(),
+            resolution: Resolution::new(1920, 1080),
             duration: 120.0,
-            bitrate: 5_000_000,
+            bitrate: 10_320_000,
+            video_bitrate: 10_000_000,
             size: 75_000_000,
             fps: 30.0,
             audio_codec: "aac".to_string(),
@@ -1870,6 +4765,7 @@ mod tests {
             container: "mp4".to_string(),
             profile: "high".to_string(),
             color_space: "rec709".to_string(),
+            ..Default::default()
         }
     }
 
@@ -1959,7 +4855,7 @@ mod tests {
     fn test_video_metadata_comprehensive() {
         let mut metadata = create_test_metadata();
         metadata.codec = "hevc".to_string();
-        metadata.resolution = "3840x2160".to_string();
+        metadata.resolution = Resolution::new(3840, 2160);
         metadata.duration = 7200.0;
         metadata.bitrate = 25000000;
         metadata.size = 2000000000;
@@ -1990,13 +4886,13 @@ mod tests {
 
         let mut video2 = create_test_metadata();
         video2.codec = "hevc".to_string();
-        video2.resolution = "3840x2160".to_string();
+        video2.resolution = Resolution::new(3840, 2160);
         video2.duration = 180.0;
         video2.bitrate = 15000000;
         video2.size = 300000000;
 
         let mut video3 = create_test_metadata();
-        video3.resolution = "1280x720".to_string();
+        video3.resolution = Resolution::new(1280, 720);
         video3.duration = 90.0;
         video3.bitrate = 3000000;
         video3.size = 50000000;
@@ -2105,10 +5001,19 @@ mod tests {
         // Add video files
         std::fs::write(temp_dir.path().join("video1.mp4"), "content").unwrap();
         std::fs::write(temp_dir.path().join("video2.avi"), "content").unwrap();
-        std::fs::write(temp_dir.path().join("video3.mkv"), "content").unwrap(); // Should be ignored
+        std::fs::write(temp_dir.path().join("video3.mkv"), "content").unwrap();
+        std::fs::write(temp_dir.path().join("master.MOV"), "content").unwrap();
+        std::fs::write(temp_dir.path().join("master.mxf"), "content").unwrap();
+        std::fs::write(temp_dir.path().join("video4.webm"), "content").unwrap(); // Should be ignored
 
         let result = validate_directory(temp_dir.path()).unwrap();
-        assert_eq!(result.len(), 2);
+        assert_eq!(result.len(), 5);
+        assert!(result
+            .iter()
+            .any(|p| p.file_name().unwrap() == "video3.mkv"));
+        assert!(result
+            .iter()
+            .any(|p| p.file_name().unwrap() == "master.MOV"));
         assert!(result
             .iter()
             .any(|p| p.file_name().unwrap() == "video1.mp4"));
@@ -2131,193 +5036,1853 @@ mod tests {
     }
 
     #[test]
-    fn test_write_conversion_report_empty() {
-        let temp_dir = tempdir().unwrap();
-        let summary = ProcessingSummary::new();
+    fn test_write_conversion_report_empty() {
+        let temp_dir = tempdir().unwrap();
+        let summary = ProcessingSummary::new();
+
+        write_conversion_report(temp_dir.path(), &summary).unwrap();
+
+        let report_path = temp_dir.path().join("conversion_report.txt");
+        assert!(report_path.exists());
+
+        let content = std::fs::read_to_string(report_path).unwrap();
+        assert!(content.contains("Total Videos: 0"));
+        assert!(content.contains("00:00:00.00"));
+    }
+
+    #[test]
+    fn test_init_logging_safe() {
+        // Test that init_logging exists and can be called
+        // Note: In actual usage, init_logging may only work once per process
+        // so we just test that the function exists and doesn't immediately panic
+        use std::sync::Once;
+        static INIT: Once = Once::new();
+
+        INIT.call_once(|| {
+            init_logging(); // Only call once to avoid global subscriber conflict
+        });
+
+        // Test passes if we get here without panicking
+    }
+
+    #[test]
+    fn test_video_error_from_io() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "access denied");
+        let video_err: VideoError = io_err.into();
+
+        match video_err {
+            VideoError::Io(e) => assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied),
+            _ => panic!("Expected Io variant"),
+        }
+    }
+
+    #[test]
+    fn test_processing_summary_debug() {
+        use std::collections::HashMap;
+
+        let summary = ProcessingSummary {
+            total_videos: 5,
+            total_size: 1000000,
+            total_duration: 300.0,
+            codecs: HashMap::from([("h264".to_string(), 3), ("hevc".to_string(), 2)]),
+            audio_codecs: HashMap::from([("aac".to_string(), 3), ("pcm_s24le".to_string(), 2)]),
+            resolutions: HashMap::from([
+                ("1920x1080".to_string(), 4),
+                ("3840x2160".to_string(), 1),
+            ]),
+        };
+
+        let debug_str = format!("{:?}", summary);
+        assert!(debug_str.contains("total_videos: 5"));
+        assert!(debug_str.contains("total_size: 1000000"));
+        assert!(debug_str.contains("total_duration: 300"));
+    }
+
+    #[test]
+    fn test_check_hardware_support_error_paths() {
+        // This test exercises the error handling paths in check_hardware_support
+        // It will likely fail since we don't have NVIDIA hardware in CI,
+        // but it ensures the error paths are covered
+        let result = check_hardware_support();
+
+        match result {
+            Ok(_) => {
+                // Hardware available - good!
+                println!("Hardware acceleration available");
+            }
+            Err(VideoError::HWAccel(_)) => {
+                // Expected in most CI environments without GPU
+                println!("Hardware acceleration not available (expected in CI)");
+            }
+            Err(VideoError::FFmpeg(_)) => {
+                // FFmpeg not available or doesn't support nvenc
+                println!("FFmpeg error (expected if nvenc not available)");
+            }
+            Err(e) => {
+                panic!("Unexpected error type: {}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_content_standards_loading() {
+        let standards = ContentStandards::load_default().unwrap();
+
+        // Test video standards
+        assert!(standards
+            .video
+            .preferred_resolutions
+            .contains(&Resolution::new(1920, 1080)));
+        assert!(standards
+            .video
+            .preferred_resolutions
+            .contains(&Resolution::new(1280, 720)));
+        assert!(standards
+            .video
+            .preferred_codecs
+            .contains(&"h264".to_string()));
+        assert!(standards
+            .video
+            .unsupported_containers
+            .contains(&"mkv".to_string()));
+
+        // Test audio standards
+        assert!(standards
+            .audio
+            .preferred_codecs
+            .contains(&"pcm".to_string()));
+        assert!(standards
+            .audio
+            .acceptable_codecs
+            .contains(&"aac".to_string()));
+
+        // Test quality standards
+        assert!(standards
+            .quality
+            .color_spaces
+            .contains(&"rec709".to_string()));
+        assert!(standards
+            .quality
+            .hdr_restrictions
+            .contains(&"hdr10".to_string()));
+    }
+
+    #[test]
+    fn test_load_standards_from_each_format() {
+        let temp = tempdir().unwrap();
+        let defaults = ContentStandards::load_default().unwrap();
+
+        let files = [
+            ("standards.toml", toml::to_string(&defaults).unwrap()),
+            (
+                "standards.yaml",
+                serde_norway::to_string(&defaults).unwrap(),
+            ),
+            ("standards.json", serde_json::to_string(&defaults).unwrap()),
+        ];
+
+        for (name, contents) in files {
+            let path = temp.path().join(name);
+            fs::write(&path, contents).unwrap();
+
+            let standards = ContentStandards::load_from_path(&path).unwrap();
+            assert_eq!(
+                standards.video.preferred_resolutions, defaults.video.preferred_resolutions,
+                "Failed for {}",
+                name
+            );
+            assert_eq!(standards.audio.sample_rates, defaults.audio.sample_rates);
+        }
+    }
+
+    #[test]
+    fn test_load_standards_reports_offending_key() {
+        let temp = tempdir().unwrap();
+        let mut standards = ContentStandards::load_default().unwrap();
+        standards
+            .video
+            .bitrate_ranges
+            .get_mut("screen_capture")
+            .unwrap()
+            .min_kbps = 9000;
+        let path = temp.path().join("bad_range.json");
+        fs::write(&path, serde_json::to_string(&standards).unwrap()).unwrap();
+
+        let err = ContentStandards::load_from_path(&path).unwrap_err();
+        assert!(matches!(err, VideoError::Standards(_)));
+        assert!(err
+            .to_string()
+            .contains("video.bitrate_ranges.screen_capture: min_kbps (9000)"));
+
+        // Type errors point at the key path
+        let mut value = serde_json::to_value(ContentStandards::load_default().unwrap()).unwrap();
+        value["video"]["preferred_frame_rates"][2] = serde_json::json!("fast");
+        fs::write(&path, value.to_string()).unwrap();
+        let err = ContentStandards::load_from_path(&path).unwrap_err();
+        assert!(err.to_string().contains("video.preferred_frame_rates[2]"));
+
+        // Misspelled keys are rejected instead of silently ignored
+        let mut value = serde_json::to_value(ContentStandards::load_default().unwrap()).unwrap();
+        value["audio"]["sample_rate"] = serde_json::json!([48000]);
+        fs::write(&path, value.to_string()).unwrap();
+        let err = ContentStandards::load_from_path(&path).unwrap_err();
+        assert!(err.to_string().contains("sample_rate"));
+    }
+
+    #[test]
+    fn test_load_standards_rejects_bad_values() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("standards.json");
+        let mut value = serde_json::to_value(ContentStandards::load_default().unwrap()).unwrap();
+        value["video"]["acceptable_resolutions"][1] = serde_json::json!("1280by800");
+        fs::write(&path, value.to_string()).unwrap();
+        let err = ContentStandards::load_from_path(&path).unwrap_err();
+        assert!(err.to_string().contains("video.acceptable_resolutions[1]"));
+
+        let mut standards = ContentStandards::load_default().unwrap();
+        standards.quality.chroma_subsampling.push("420".to_string());
+        assert!(standards
+            .validate()
+            .unwrap_err()
+            .contains("quality.chroma_subsampling[2]"));
+
+        let path = temp.path().join("standards.ini");
+        fs::write(&path, "").unwrap();
+        let err = ContentStandards::load_from_path(&path).unwrap_err();
+        assert!(err.to_string().contains("unsupported standards format"));
+    }
+
+    #[test]
+    fn test_compliance_engine_with_custom_standards() {
+        let mut standards = ContentStandards::load_default().unwrap();
+        standards.video.preferred_codecs = vec!["prores".to_string()];
+        let engine = ComplianceEngine::with_standards(standards);
+
+        let result = engine.analyze_compliance(&create_test_metadata());
+        assert!(!result.is_compliant);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::VideoCodec));
+    }
+
+    #[test]
+    fn test_compliance_engine_creation() {
+        let engine = ComplianceEngine::new();
+        assert!(engine.is_ok());
+
+        let engine = engine.unwrap();
+        let standards = engine.get_standards();
+        assert!(standards
+            .video
+            .preferred_codecs
+            .contains(&"h264".to_string()));
+    }
+
+    #[test]
+    fn test_compliance_analysis_compliant_file() {
+        let engine = ComplianceEngine::new().unwrap();
+        let metadata = create_test_metadata(); // h264, 1920x1080, aac, mp4, rec709 - should be compliant
+
+        let result = engine.analyze_compliance(&metadata);
+        assert!(result.is_compliant);
+        assert!(result.score >= 90); // Should have high score for compliant file
+        assert!(result
+            .violations
+            .iter()
+            .all(|v| matches!(v.severity, ViolationSeverity::Info)));
+    }
+
+    #[test]
+    fn test_compliance_analysis_non_compliant_file() {
+        let engine = ComplianceEngine::new().unwrap();
+        let mut metadata = create_test_metadata();
+
+        // Make it non-compliant
+        metadata.codec = "mpeg4".to_string(); // Not preferred
+        metadata.container = "mkv".to_string(); // Unsupported
+        metadata.color_space = "bt2020nc".to_string();
+        metadata.color_primaries = "bt2020".to_string(); // Unsupported wide gamut
+
+        let result = engine.analyze_compliance(&metadata);
+        assert!(!result.is_compliant);
+        assert!(result.score < 90); // Should have low score
+        assert!(!result.violations.is_empty());
+        assert!(!result.recommendations.is_empty());
+
+        // Check for specific violations
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| matches!(v.category, ViolationCategory::VideoCodec)));
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| matches!(v.category, ViolationCategory::Container)));
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| matches!(v.category, ViolationCategory::HDR)));
+    }
+
+    #[test]
+    fn test_hdr_compliance_classification() {
+        let engine = ComplianceEngine::new().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.color_transfer = "bt709".to_string();
+        metadata.color_primaries = "bt709".to_string();
+
+        let result = engine.analyze_compliance(&metadata);
+        assert_eq!(result.hdr_format, HdrFormat::Sdr);
+        assert!(!result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::HDR));
+
+        // HDR10 is signalled by the PQ transfer, not by color_space
+        metadata.color_transfer = "smpte2084".to_string();
+        metadata.color_primaries = "bt2020".to_string();
+        metadata.hdr.content_light_level = Some(hdr::ContentLightLevel {
+            max_cll: 1000,
+            max_fall: 400,
+        });
+        let result = engine.analyze_compliance(&metadata);
+        assert_eq!(result.hdr_format, HdrFormat::Hdr10);
+        let hdr_violations: Vec<_> = result
+            .violations
+            .iter()
+            .filter(|v| v.category == ViolationCategory::HDR)
+            .collect();
+        assert_eq!(hdr_violations.len(), 1);
+        assert!(matches!(
+            hdr_violations[0].severity,
+            ViolationSeverity::Critical
+        ));
+        assert_eq!(
+            hdr_violations[0].current_value,
+            "HDR10 (smpte2084, bt2020, MaxCLL 1000, MaxFALL 400)"
+        );
+
+        metadata.color_transfer = "arib-std-b67".to_string();
+        metadata.hdr = HdrMetadata::default();
+        let result = engine.analyze_compliance(&metadata);
+        assert_eq!(result.hdr_format, HdrFormat::Hlg);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.description == "HLG content not supported by delivery pipeline"));
+
+        // Wide-gamut SDR is still flagged through the primaries
+        metadata.color_transfer = "bt709".to_string();
+        let result = engine.analyze_compliance(&metadata);
+        assert_eq!(result.hdr_format, HdrFormat::Sdr);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.description == "Color space not supported by delivery pipeline"));
+
+        // Display P3 primaries are flagged and converted to BT.709
+        metadata.color_space = "bt709".to_string();
+        metadata.color_primaries = "smpte432".to_string();
+        let standards = ContentStandards::load_default().unwrap();
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.current_value == "bt709 (primaries smpte432)"));
+        let filters = color_conversion_filters(&result, &metadata, &standards);
+        assert!(filters[0].contains("pin=smpte432"));
+    }
+
+    #[test]
+    fn test_frame_rate_matches() {
+        let test_cases = [
+            (30000.0 / 1001.0, 29.97, true),
+            (29.97, 29.97, true),
+            (30000.0 / 1001.0, 30.0, false),
+            (30.0, 29.97, false),
+            (24000.0 / 1001.0, 23.976, true),
+            (24.0, 23.976, false),
+            (60000.0 / 1001.0, 59.94, true),
+            (25.0, 25.0, true),
+            (15.0, 15.0, true),
+            (14.5, 15.0, false),
+        ];
+
+        for (actual, standard, expected) in test_cases {
+            assert_eq!(
+                frame_rate_matches(actual, standard),
+                expected,
+                "Failed for {} vs {}",
+                actual,
+                standard
+            );
+        }
+    }
+
+    #[test]
+    fn test_frame_rate_compliance_by_content_type() {
+        let engine = ComplianceEngine::new().unwrap();
+        let frame_rate_violations = |metadata: &VideoMetadata| -> Vec<ComplianceViolation> {
+            engine
+                .analyze_compliance(metadata)
+                .violations
+                .into_iter()
+                .filter(|v| v.category == ViolationCategory::FrameRate)
+                .collect()
+        };
+
+        // NTSC rate reported by ffprobe as 30000/1001
+        let mut metadata = create_test_metadata();
+        metadata.fps = 30000.0 / 1001.0;
+        assert!(frame_rate_violations(&metadata).is_empty());
+
+        // 24 fps is fine for live action but not for a screen capture
+        metadata.fps = 24.0;
+        assert!(frame_rate_violations(&metadata).is_empty());
+        metadata.file_name = "screen_recording.mp4".to_string();
+        let violations = frame_rate_violations(&metadata);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(violations[0].severity, ViolationSeverity::Warning));
+        assert!(violations[0].expected_value.contains("ScreenCapture"));
+
+        // 60 fps is not a preferred rate for any content type
+        metadata.fps = 60.0;
+        let result = engine.analyze_compliance(&metadata);
+        assert!(!result.is_compliant);
+        assert!(result.score <= 90);
+        assert!(
+            result
+                .violations
+                .iter()
+                .any(|v| v.category == ViolationCategory::FrameRate
+                    && v.current_value == "60.000 fps")
+        );
+    }
+
+    #[test]
+    fn test_bitrate_compliance_by_content_type() {
+        let engine = ComplianceEngine::new().unwrap();
+        let bitrate_violation = |metadata: &VideoMetadata| -> Option<ComplianceViolation> {
+            engine
+                .analyze_compliance(metadata)
+                .violations
+                .into_iter()
+                .find(|v| v.category == ViolationCategory::Bitrate)
+        };
+
+        // 10 Mbps 1080p30 live action is within 8000–15000 kbps
+        let mut metadata = create_test_metadata();
+        assert!(bitrate_violation(&metadata).is_none());
+
+        // The same stream is too high for a screen capture
+        metadata.file_name = "screen_capture_01.mp4".to_string();
+        let violation = bitrate_violation(&metadata).unwrap();
+        assert_eq!(violation.current_value, "10000 kbps");
+        assert!(violation
+            .description
+            .contains("above the Screen Capture range"));
+        assert!(violation.expected_value.contains("6000–8000 kbps"));
+        assert!(violation
+            .expected_value
+            .contains("file name contains 'screen'"));
+
+        // Stream bitrate is used instead of the container bitrate
+        metadata.file_name = String::new();
+        metadata.bitrate = 10_000_000;
+        metadata.video_bitrate = 5_000_000;
+        let violation = bitrate_violation(&metadata).unwrap();
+        assert_eq!(violation.current_value, "5000 kbps");
+        assert!(violation.expected_value.contains("1080p at 24-30 fps"));
+    }
+
+    #[test]
+    fn test_profile_compliance() {
+        let engine = ComplianceEngine::new().unwrap();
+        let profile_violations = |metadata: &VideoMetadata| -> Vec<ComplianceViolation> {
+            engine
+                .analyze_compliance(metadata)
+                .violations
+                .into_iter()
+                .filter(|v| v.category == ViolationCategory::Profile)
+                .collect()
+        };
+
+        let mut metadata = create_test_metadata();
+        metadata.level = 41;
+        assert!(profile_violations(&metadata).is_empty());
+
+        metadata.profile = "constrained baseline".to_string();
+        let violations = profile_violations(&metadata);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(violations[0].severity, ViolationSeverity::Warning));
+
+        for profile in ["high 10", "high 4:2:2 intra", "high 4:4:4 predictive"] {
+            metadata.profile = profile.to_string();
+            let violations = profile_violations(&metadata);
+            assert!(
+                matches!(violations[0].severity, ViolationSeverity::Critical),
+                "Failed for {}",
+                profile
+            );
+        }
+
+        // Level above the per-profile maximum
+        metadata.profile = "high".to_string();
+        metadata.level = 52;
+        let violations = profile_violations(&metadata);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].current_value, "5.2");
+        assert_eq!(violations[0].expected_value, "≤ 5.1");
+
+        let mut standards = ContentStandards::load_default().unwrap();
+        standards.video.max_levels.insert("high".to_string(), 4.1);
+        let strict = ComplianceEngine::with_standards(standards);
+        metadata.level = 42;
+        assert!(strict
+            .analyze_compliance(&metadata)
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::Profile));
+    }
+
+    #[test]
+    fn test_keyframe_interval_compliance() {
+        let engine = ComplianceEngine::new().unwrap();
+        let mut metadata = create_test_metadata();
+        let gop = |typical_gop_seconds: f64| GopAnalysis {
+            keyframe_count: 60,
+            typical_gop_seconds,
+            min_gop_seconds: 0.5,
+            max_gop_seconds: 4.0,
+            mean_gop_seconds: typical_gop_seconds - 0.1,
+            closed_gop: true,
+        };
+
+        // 59 frames at 29.97 fps still counts as a 2 second interval
+        metadata.gop = Some(gop(1.969));
+        let result = engine.analyze_compliance(&metadata);
+        assert!(!result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::Keyframe));
+
+        metadata.gop = Some(gop(1.0));
+        let result = engine.analyze_compliance(&metadata);
+        let violation = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::Keyframe)
+            .unwrap();
+        assert_eq!(
+            violation.current_value,
+            "1.00 s (min 0.50 s, max 4.00 s, mean 0.90 s, closed GOP)"
+        );
+        assert_eq!(violation.expected_value, "≥ 2 s");
+        assert!(!result.is_compliant);
+    }
+
+    #[test]
+    fn test_keyframe_fix_sets_gop_size() {
+        let standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.fps = 30000.0 / 1001.0;
+        let result = ComplianceResult {
+            is_compliant: false,
+            score: 90,
+            hdr_format: HdrFormat::Sdr,
+            violations: vec![ComplianceViolation {
+                severity: ViolationSeverity::Warning,
+                category: ViolationCategory::Keyframe,
+                description: "Keyframe interval too short".to_string(),
+                current_value: "1.00 s".to_string(),
+                expected_value: "≥ 2 s".to_string(),
+            }],
+            recommendations: vec![],
+        };
+
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &metadata,
+            &standards,
+            EncoderBackend::Nvenc,
+        );
+        let args = args.join(" ");
+        assert!(args.contains("-c:v h264_nvenc"));
+        assert!(args.contains("-g 60 -keyint_min 60"));
+
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        );
+        let args = args.join(" ");
+        assert!(args.contains("-c:v libx264"));
+        assert!(args.contains("-preset medium -crf 18 -tune film"));
+        assert!(args.contains("-g 60 -keyint_min 60"));
+
+        assert!(keyframe_interval_args(0.0, 2).is_empty());
+    }
+
+    #[test]
+    fn test_parse_pixel_format() {
+        let cases = [
+            ("yuv420p", "4:2:0", 8),
+            ("yuvj420p", "4:2:0", 8),
+            ("yuv420p10le", "4:2:0", 10),
+            ("yuv422p10be", "4:2:2", 10),
+            ("yuv444p12le", "4:4:4", 12),
+            ("yuva444p", "4:4:4", 8),
+            ("nv12", "4:2:0", 8),
+            ("p010le", "4:2:0", 10),
+            ("v210", "4:2:2", 10),
+            ("gbrp10le", "4:4:4", 10),
+            ("rgb24", "4:4:4", 8),
+            ("rgb48le", "4:4:4", 16),
+            ("gray", "4:0:0", 8),
+        ];
+        for (pix_fmt, subsampling, depth) in cases {
+            assert_eq!(
+                parse_pixel_format(pix_fmt),
+                Some((subsampling.to_string(), depth)),
+                "Failed for {}",
+                pix_fmt
+            );
+        }
+
+        assert_eq!(parse_pixel_format("unknown"), None);
+        assert_eq!(parse_pixel_format("yuv999p"), None);
+    }
+
+    #[test]
+    fn test_pixel_format_compliance() {
+        let engine = ComplianceEngine::new().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.pix_fmt = "yuv420p".to_string();
+        metadata.chroma_subsampling = "4:2:0".to_string();
+        metadata.bit_depth = 8;
+
+        let result = engine.analyze_compliance(&metadata);
+        assert!(!result.violations.iter().any(|v| {
+            v.category == ViolationCategory::ChromaSubsampling
+                || v.category == ViolationCategory::BitDepth
+        }));
+
+        metadata.pix_fmt = "yuv444p10le".to_string();
+        metadata.chroma_subsampling = "4:4:4".to_string();
+        metadata.bit_depth = 10;
+        let result = engine.analyze_compliance(&metadata);
+
+        let chroma = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::ChromaSubsampling)
+            .expect("4:4:4 should be flagged");
+        assert_eq!(chroma.current_value, "4:4:4 (yuv444p10le)");
+        assert_eq!(chroma.expected_value, "4:2:0, 4:2:2");
+
+        let depth = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::BitDepth)
+            .expect("10-bit should be flagged");
+        assert_eq!(depth.current_value, "10-bit (yuv444p10le)");
+        assert_eq!(depth.expected_value, "8-bit");
+
+        // Unknown pixel formats are not flagged
+        metadata.chroma_subsampling = String::new();
+        metadata.bit_depth = 0;
+        let result = engine.analyze_compliance(&metadata);
+        assert!(!result.violations.iter().any(|v| {
+            v.category == ViolationCategory::ChromaSubsampling
+                || v.category == ViolationCategory::BitDepth
+        }));
+    }
+
+    #[test]
+    fn test_audio_bit_depth_and_layout_parsing() {
+        let pcm = serde_json::json!({ "sample_fmt": "s32", "bits_per_sample": 24 });
+        assert_eq!(audio_bit_depth("pcm_s24le", &pcm), 24);
+        assert_eq!(audio_bit_depth("pcm_s16le", &serde_json::json!({})), 16);
+
+        let alac = serde_json::json!({ "sample_fmt": "s32p", "bits_per_raw_sample": "24" });
+        assert_eq!(audio_bit_depth("alac", &alac), 24);
+
+        let aac = serde_json::json!({ "sample_fmt": "fltp", "bits_per_sample": 0 });
+        assert_eq!(audio_bit_depth("aac", &aac), 0);
+
+        assert_eq!(audio_codec_family("pcm_s24le"), "pcm");
+        assert_eq!(audio_codec_family("aac"), "aac");
+
+        assert_eq!(channel_layout_name(6, "5.1(side)"), "5.1");
+        assert_eq!(channel_layout_name(1, "unknown"), "mono");
+        assert_eq!(channel_layout_name(6, ""), "5.1");
+        assert_eq!(channel_layout_name(4, ""), "4 channels");
+        assert_eq!(channel_count_designation(2), Some("2.0"));
+    }
+
+    #[test]
+    fn test_audio_format_compliance() {
+        let engine = ComplianceEngine::new().unwrap();
+        let audio_violations = |metadata: &VideoMetadata| -> Vec<ComplianceViolation> {
+            engine
+                .analyze_compliance(metadata)
+                .violations
+                .into_iter()
+                .filter(|v| v.category == ViolationCategory::Audio)
+                .collect()
+        };
+
+        let mut metadata = create_test_metadata();
+        metadata.audio_codec = "pcm_s24le".to_string();
+        metadata.audio_bit_depth = 24;
+        metadata.audio_channels = 2;
+        metadata.audio_channel_layout = "stereo".to_string();
+        assert!(audio_violations(&metadata).is_empty());
+        // pcm_s24le is the preferred "pcm" codec
+        assert!(!engine
+            .analyze_compliance(&metadata)
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::AudioCodec));
+
+        metadata.audio_sample_rate = 96000;
+        metadata.audio_bit_depth = 32;
+        let violations = audio_violations(&metadata);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].current_value, "96000 Hz");
+        assert_eq!(violations[0].expected_value, "44100 Hz, 48000 Hz");
+        assert_eq!(violations[1].current_value, "32-bit (pcm_s24le)");
+
+        let mut metadata = create_test_metadata();
+        for (channels, layout, expected) in [
+            (1, "mono", Some("mono (1 channel)")),
+            (6, "5.1(side)", Some("5.1 (6 channels)")),
+            (2, "downmix", Some("downmix (2 channels)")),
+            (2, "unknown", None),
+            (4, "quad", Some("quad (4 channels)")),
+            (2, "stereo", None),
+        ] {
+            metadata.audio_channels = channels;
+            metadata.audio_channel_layout = layout.to_string();
+            let violations = audio_violations(&metadata);
+            assert_eq!(
+                violations.first().map(|v| v.current_value.as_str()),
+                expected,
+                "Failed for {}",
+                layout
+            );
+        }
+    }
+
+    #[test]
+    fn test_aac_bitrate_compliance() {
+        let engine = ComplianceEngine::new().unwrap();
+        let aac_violations = |metadata: &VideoMetadata| -> Vec<ComplianceViolation> {
+            engine
+                .analyze_compliance(metadata)
+                .violations
+                .into_iter()
+                .filter(|v| v.category == ViolationCategory::Audio)
+                .collect()
+        };
+        let cbr = AudioBitrateAnalysis {
+            packet_count: 2344,
+            mean_packet_bytes: 853.3,
+            packet_size_variance: 0.2,
+            mean_kbps: 320.0,
+            bitrate_variation: 0.001,
+            constant_bitrate: true,
+        };
+
+        let mut metadata = create_test_metadata();
+        metadata.audio_packets = Some(cbr.clone());
+        assert!(aac_violations(&metadata).is_empty());
+
+        metadata.audio_bitrate = 128_000;
+        let violations = aac_violations(&metadata);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].description, "AAC bitrate below required");
+        assert_eq!(violations[0].current_value, "128 kbps");
+        assert_eq!(violations[0].expected_value, "320 kbps CBR");
+
+        // Without a declared bitrate the measured one is used
+        metadata.audio_bitrate = 0;
+        metadata.audio_packets = Some(AudioBitrateAnalysis {
+            mean_kbps: 192.0,
+            bitrate_variation: 0.3,
+            constant_bitrate: false,
+            ..cbr
+        });
+        let violations = aac_violations(&metadata);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].current_value, "192 kbps");
+        assert_eq!(violations[1].description, "AAC audio is variable bitrate");
+        assert!(violations[1].current_value.starts_with("VBR 192 kbps"));
+
+        // PCM has no bitrate rule
+        metadata.audio_codec = "pcm_s24le".to_string();
+        assert!(aac_violations(&metadata).is_empty());
+    }
+
+    #[test]
+    fn test_aspect_ratio_parsing() {
+        assert_eq!(parse_ratio("16:9"), Some((16, 9)));
+        assert_eq!(parse_ratio("30000/1001"), Some((30000, 1001)));
+        assert_eq!(parse_ratio("0:1"), None);
+        assert_eq!(parse_ratio("N/A"), None);
+
+        let mut metadata = create_test_metadata();
+        assert!(metadata.has_square_pixels());
+        assert_eq!(metadata.display_aspect_ratio_name(), "16:9");
+
+        // HDV: 1440x1080 stored with 4:3 pixels displays as 16:9
+        metadata.resolution = Resolution::new(1440, 1080);
+        metadata.sample_aspect_ratio = "4:3".to_string();
+        assert!(!metadata.has_square_pixels());
+        assert!((metadata.display_aspect().unwrap() - 16.0 / 9.0).abs() < 1e-9);
+        assert_eq!(metadata.display_aspect_ratio_name(), "16:9");
+
+        // Unknown SAR is treated as square
+        metadata.sample_aspect_ratio = "0:1".to_string();
+        assert!(metadata.has_square_pixels());
+        assert_eq!(metadata.display_aspect_ratio_name(), "4:3");
+    }
+
+    #[test]
+    fn test_aspect_ratio_compliance() {
+        let engine = ComplianceEngine::new().unwrap();
+        let aspect_violations = |metadata: &VideoMetadata| -> Vec<ComplianceViolation> {
+            engine
+                .analyze_compliance(metadata)
+                .violations
+                .into_iter()
+                .filter(|v| v.category == ViolationCategory::AspectRatio)
+                .collect()
+        };
+
+        let mut metadata = create_test_metadata();
+        for resolution in ["1920x1080", "1366x768", "1280x800", "1080x1920"] {
+            metadata.resolution = resolution.parse().unwrap();
+            assert!(
+                aspect_violations(&metadata).is_empty(),
+                "Failed for {}",
+                resolution
+            );
+        }
+
+        // Standard-looking dimensions, but anamorphic 4:3 display
+        metadata.resolution = Resolution::new(1920, 1080);
+        metadata.sample_aspect_ratio = "3:4".to_string();
+        metadata.display_aspect_ratio = "4:3".to_string();
+        let violations = aspect_violations(&metadata);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].current_value, "SAR 3:4, DAR 4:3 (1920x1080)");
+        assert_eq!(violations[1].current_value, "4:3 (1.333)");
+        assert_eq!(violations[1].expected_value, "16:9, 16:10, 9:16");
+
+        // Square pixels with an odd ratio
+        metadata.resolution = Resolution::new(1080, 1080);
+        metadata.sample_aspect_ratio = "1:1".to_string();
+        metadata.display_aspect_ratio = "1:1".to_string();
+        let violations = aspect_violations(&metadata);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].description,
+            "Display aspect ratio not supported"
+        );
+    }
+
+    #[test]
+    fn test_anamorphic_fix_resamples_to_square_pixels() {
+        let standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.resolution = Resolution::new(1440, 1080);
+        metadata.sample_aspect_ratio = "4:3".to_string();
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        )
+        .join(" ");
+        assert!(args.contains("-vf scale=trunc(iw*sar/2)*2:ih,setsar=1"));
+    }
+
+    #[test]
+    fn test_resolution_fix_preserves_orientation() {
+        let mut standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.resolution = Resolution::new(1080, 1350);
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        assert!(result
+            .recommendations
+            .contains(&"Resize to 1080x1920 (letterbox pad)".to_string()));
+
+        let fixes = |standards: &ContentStandards| {
+            generate_optimized_video_fixes(
+                &result,
+                &ContentType::LiveAction,
+                &metadata,
+                standards,
+                EncoderBackend::Libx264,
+            )
+            .join(" ")
+        };
+        assert!(fixes(&standards).contains(
+            "-vf scale=1080:1920:force_original_aspect_ratio=decrease:force_divisible_by=2,\
+             pad=1080:1920:(ow-iw)/2:(oh-ih)/2"
+        ));
+
+        standards.video.scaling_policy = ScalingPolicy::Crop;
+        assert!(fixes(&standards).contains("crop=1080:1920"));
+    }
+
+    #[test]
+    fn test_vertical_video_rules() {
+        let mut standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.file_name = "screen-recording.mp4".to_string();
+        metadata.resolution = Resolution::new(1080, 1920);
+        metadata.fps = 30.0;
+
+        let (content_type, reason) =
+            detect_content_type_with_reason(&metadata, Path::new(&metadata.file_name));
+        assert_eq!(content_type, ContentType::Vertical);
+        assert_eq!(reason, "portrait 1080x1920 frame");
+
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        assert!(!result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::Resolution));
+        assert!(result
+            .recommendations
+            .iter()
+            .any(|r| r.contains("lower third")));
+
+        // Undersized portrait footage is scaled up to a portrait resolution
+        metadata.resolution = Resolution::new(540, 960);
+        metadata.fps = 60.0;
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        let violation = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::Resolution)
+            .unwrap();
+        assert_eq!(
+            violation.expected_value,
+            "Preferred: 720x1280, 1080x1920 (vertical)"
+        );
+        let frame_rate = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::FrameRate)
+            .unwrap();
+        assert_eq!(
+            frame_rate.expected_value,
+            "23.976, 24, 25, 29.97, 30 (Vertical)"
+        );
+
+        let fixes = |standards: &ContentStandards| {
+            generate_optimized_video_fixes(
+                &result,
+                &ContentType::Vertical,
+                &metadata,
+                standards,
+                EncoderBackend::Libx264,
+            )
+            .join(" ")
+        };
+        // 60 fps is also brought down to 30 fps by dropping every other frame
+        assert!(fixes(&standards).contains("-vf fps=30,scale=720:1280"));
+
+        // Without portrait resolutions the footage is never rotated or squashed
+        standards.video.preferred_resolutions = vec![Resolution::HD, Resolution::FULL_HD];
+        standards.video.acceptable_resolutions.clear();
+        let args = fixes(&standards);
+        assert!(args.ends_with("-vf fps=30"));
+        assert!(!args.contains("scale="));
+        assert!(!args.contains("transpose"));
+    }
+
+    #[test]
+    fn test_stream_rotation() {
+        let display_matrix = serde_json::json!({
+            "side_data_list": [{ "side_data_type": "Display Matrix", "rotation": -90 }]
+        });
+        assert_eq!(stream_rotation(&display_matrix), 90);
+        let display_matrix = serde_json::json!({
+            "side_data_list": [{ "side_data_type": "Display Matrix", "rotation": 90 }]
+        });
+        assert_eq!(stream_rotation(&display_matrix), 270);
+        assert_eq!(
+            stream_rotation(&serde_json::json!({ "tags": { "rotate": "180" } })),
+            180
+        );
+        assert_eq!(stream_rotation(&serde_json::json!({})), 0);
+    }
+
+    #[test]
+    fn test_rotated_phone_footage() {
+        let standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.display_aspect_ratio = "16:9".to_string();
+        metadata.rotation = 90;
+
+        assert_eq!(metadata.effective_resolution(), Resolution::new(1080, 1920));
+        assert_eq!(metadata.display_aspect_ratio_name(), "9:16");
+        assert_eq!(
+            detect_content_type(&metadata, Path::new("clip.mp4")),
+            ContentType::Vertical
+        );
+
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        let categories: Vec<_> = result.violations.iter().map(|v| &v.category).collect();
+        assert!(!categories.contains(&&ViolationCategory::Resolution));
+        assert!(!categories.contains(&&ViolationCategory::AspectRatio));
+        let rotation = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::Rotation)
+            .unwrap();
+        assert_eq!(
+            rotation.current_value,
+            "90° (stored 1920x1080, displayed 1080x1920)"
+        );
+
+        // ffmpeg's default autorotate turns the frames upright and drops the
+        // display matrix; no manual transpose that would rotate twice
+        let plan = FixPlan::new(
+            Path::new("/in/clip.mp4"),
+            Path::new("/out"),
+            &result,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        );
+        assert_eq!(
+            plan.ffmpeg_args.join(" "),
+            "-i /in/clip.mp4 -c:v libx264 -profile:v high -pix_fmt yuv420p -preset medium -crf 18 \
+             -tune film -g 60 -keyint_min 60 -c:a copy -movflags +faststart -y /out/clip.mp4"
+        );
+        assert_eq!(plan.target.resolution, Resolution::new(1080, 1920));
+    }
+
+    #[test]
+    fn test_variable_frame_rate_conformed() {
+        let standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.file_name = "screen-recording.mp4".to_string();
+        metadata.fps = 60.0;
+        metadata.frame_timing = stream_analysis::analyze_frame_timing(&[], 60.0, 27.4);
+        assert!(metadata.is_variable_frame_rate());
+
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        let vfr = result
+            .violations
+            .iter()
+            .find(|v| v.description == "Variable frame rate")
+            .unwrap();
+        assert_eq!(vfr.category, ViolationCategory::FrameRate);
+        assert_eq!(
+            vfr.current_value,
+            "VFR: average 27.400 fps vs nominal 60 fps"
+        );
+        assert_eq!(vfr.expected_value, "Constant frame rate (15, 29.97, 30)");
+        assert!(result
+            .recommendations
+            .contains(&"Conform to a constant 29.97 fps".to_string()));
+
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::ScreenCapture,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        )
+        .join(" ");
+        assert!(args.contains("-vf fps=30000/1001"));
+        assert!(args.contains("-g 60"));
+
+        // Constant frame rate footage keeps its timing
+        metadata.frame_timing = stream_analysis::analyze_frame_timing(&[], 30.0, 30.0);
+        metadata.fps = 30.0;
+        let result = ComplianceEngine::with_standards(standards).analyze_compliance(&metadata);
+        assert!(!result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::FrameRate));
+    }
+
+    #[test]
+    fn test_out_of_spec_frame_rate_converted() {
+        let live_action = [23.976, 24.0, 25.0, 29.97, 30.0];
+        assert_eq!(conversion_frame_rate(60.0, &live_action), Some(30.0));
+        assert_eq!(conversion_frame_rate(50.0, &live_action), Some(25.0));
+        assert_eq!(conversion_frame_rate(59.94, &live_action), Some(29.97));
+        assert_eq!(conversion_frame_rate(120.0, &live_action), Some(30.0));
+        // No whole-number ratio: nearest allowed rate
+        assert_eq!(conversion_frame_rate(33.0, &live_action), Some(30.0));
+        assert!(!is_integer_ratio(59.94, 30.0));
+
+        let mut standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.file_name = "screen-recording.mp4".to_string();
+        metadata.fps = 60.0;
+
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        assert!(result
+            .recommendations
+            .contains(&"Convert to 30 fps (frame dropping)".to_string()));
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::ScreenCapture,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        )
+        .join(" ");
+        assert!(args.contains("-vf fps=30"));
+        assert!(args.contains("-g 60"));
+
+        // Re-encoded audio is resampled to the video timestamps; copied audio is left alone
+        let pcm = audio_encode_args(&metadata, &standards.audio, "mov");
+        assert!(audio_sync_args(false, &pcm).is_empty());
+        assert!(audio_sync_args(true, &["-c:a".to_string(), "copy".to_string()]).is_empty());
+        assert_eq!(
+            audio_sync_args(true, &pcm),
+            vec!["-af", "aresample=async=1:first_pts=0"]
+        );
+
+        // Rates without a whole-number ratio use the configured method
+        standards.video.frame_rate_conversion = FrameRateConversion::Interpolate;
+        metadata.file_name = "interview.mp4".to_string();
+        metadata.fps = 33.0;
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        assert!(result
+            .recommendations
+            .contains(&"Convert to 30 fps (motion interpolation)".to_string()));
+        let args =
+            generate_video_fixes(&result, &metadata, &standards, EncoderBackend::Libx264).join(" ");
+        assert!(args.contains("minterpolate=fps=30:mi_mode=mci"));
+
+        // 24 fps screen captures are only discouraged, not converted
+        metadata.file_name = "screen-recording.mp4".to_string();
+        metadata.fps = 24.0;
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::FrameRate));
+        assert_eq!(
+            frame_rate_target(&result, &metadata, &standards, &ContentType::ScreenCapture),
+            None
+        );
+    }
+
+    #[test]
+    fn test_audio_target_policy() {
+        let mut standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.audio_codec = "pcm_s16le".to_string();
+        metadata.audio_sample_rate = 44100;
+        metadata.audio_bit_depth = 16;
+        let audio_args = |metadata: &VideoMetadata, standards: &ContentStandards, container| {
+            audio_encode_args(metadata, &standards.audio, container).join(" ")
+        };
 
-        write_conversion_report(temp_dir.path(), &summary).unwrap();
+        // PCM keeps the source rate and depth; MP4 outputs get ALAC instead
+        assert_eq!(
+            audio_args(&metadata, &standards, "mov"),
+            "-c:a pcm_s16le -ar 44100 -ac 2"
+        );
+        assert_eq!(
+            audio_args(&metadata, &standards, "mp4"),
+            "-c:a alac -sample_fmt s16p -ar 44100 -ac 2"
+        );
 
-        let report_path = temp_dir.path().join("conversion_report.txt");
-        assert!(report_path.exists());
+        // Out-of-spec rates are resampled to 48 kHz; lossy sources get 24-bit
+        metadata.audio_codec = "opus".to_string();
+        metadata.audio_sample_rate = 96000;
+        metadata.audio_bit_depth = 0;
+        assert_eq!(
+            audio_args(&metadata, &standards, "mov"),
+            "-c:a pcm_s24le -ar 48000 -ac 2"
+        );
 
-        let content = std::fs::read_to_string(report_path).unwrap();
-        assert!(content.contains("Total Videos: 0"));
-        assert!(content.contains("00:00:00.00"));
+        standards.audio.target_codec = AudioTarget::Alac;
+        assert_eq!(
+            audio_args(&metadata, &standards, "mov"),
+            "-c:a alac -sample_fmt s32p -ar 48000 -ac 2"
+        );
+
+        standards.audio.target_codec = AudioTarget::Aac;
+        assert_eq!(
+            audio_args(&metadata, &standards, "mp4"),
+            "-c:a aac -b:a 320k -ar 48000 -ac 2"
+        );
+
+        // Compliant audio is copied
+        let compliant = create_test_metadata();
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&compliant);
+        assert_eq!(
+            generate_audio_fixes(&result, &compliant, &standards, "mp4"),
+            vec!["-c:a", "copy"]
+        );
+
+        // The filename names the codec the audio is actually written in
+        standards.audio.target_codec = AudioTarget::Pcm;
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        let filename = |input: &str| {
+            generate_compliance_output_filename(Path::new(input), &result, &standards)
+        };
+        assert_eq!(filename("/in/clip.mp4"), "clip.compliant.alac.mp4");
+        assert_eq!(filename("/in/clip.mov"), "clip.compliant.pcm.mov");
+
+        // Copied audio adds no codec to the name
+        let mut lossless = compliant.clone();
+        lossless.audio_codec = "alac".to_string();
+        assert_eq!(
+            generate_compliance_output_filename(
+                Path::new("/in/clip.mp4"),
+                &ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&lossless),
+                &standards
+            ),
+            "clip.compliant.mp4"
+        );
     }
 
     #[test]
-    fn test_init_logging_safe() {
-        // Test that init_logging exists and can be called
-        // Note: In actual usage, init_logging may only work once per process
-        // so we just test that the function exists and doesn't immediately panic
-        use std::sync::Once;
-        static INIT: Once = Once::new();
+    fn test_remux_plan_avoids_reencoding() {
+        let standards = ContentStandards::load_default().unwrap();
+        let engine = ComplianceEngine::with_standards(standards.clone());
+        let plan = |metadata: &VideoMetadata| {
+            let result = engine.analyze_compliance(metadata);
+            (
+                plan_remux(&result, metadata, &standards, &ContentType::LiveAction),
+                result,
+            )
+        };
 
-        INIT.call_once(|| {
-            init_logging(); // Only call once to avoid global subscriber conflict
-        });
+        // H.264 in MKV only needs its container changed
+        let mut metadata = create_test_metadata();
+        metadata.container = "mkv".to_string();
+        metadata.audio_codec = "alac".to_string();
+        let (remux, result) = plan(&metadata);
+        assert!(remux.is_lossless());
+        assert_eq!(remux.container, "mp4");
+        assert_eq!(remux.lossless, vec![ViolationCategory::Container]);
+        assert_eq!(remux.method(), "lossless remux to MP4");
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        );
+        assert_eq!(args, vec!["-c:v", "copy"]);
+        assert_eq!(
+            generate_optimized_output_filename(Path::new("/in/talk.mkv"), &remux.container),
+            "talk.mp4"
+        );
+        assert_eq!(
+            generate_optimized_output_filename(Path::new("/in/talk.MP4"), "mp4"),
+            "talk.MP4"
+        );
 
-        // Test passes if we get here without panicking
+        // AVI is not a preferred container; copied PCM audio goes to QuickTime;
+        // missing colour tags are written by h264_metadata
+        metadata.container = "avi".to_string();
+        metadata.audio_codec = "pcm_s16le".to_string();
+        metadata.color_space = "unknown".to_string();
+        let (remux, result) = plan(&metadata);
+        assert!(result.violations.iter().any(|v| {
+            v.category == ViolationCategory::Container
+                && v.description == "Container not in preferred list"
+        }));
+        assert!(remux.is_lossless());
+        assert_eq!(remux.container, "mov");
+        assert_eq!(
+            remux.lossless,
+            vec![ViolationCategory::Container, ViolationCategory::ColorSpace]
+        );
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        )
+        .join(" ");
+        assert_eq!(
+            args,
+            "-c:v copy -bsf:v h264_metadata=colour_primaries=1:transfer_characteristics=1:matrix_coefficients=1"
+        );
+
+        // AAC is acceptable, so H.264 and AAC in MKV are both copied
+        let mut metadata = create_test_metadata();
+        metadata.container = "mkv".to_string();
+        let (remux, result) = plan(&metadata);
+        assert!(remux.is_lossless());
+        assert_eq!(remux.audio, StreamAction::Copy);
+        assert_eq!(remux.lossless, vec![ViolationCategory::Container]);
+        assert_eq!(remux.unchanged, vec![ViolationCategory::AudioCodec]);
+        let fix_plan = FixPlan::new(
+            Path::new("/in/talk.mkv"),
+            Path::new("/out"),
+            &result,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        );
+        assert_eq!(
+            fix_plan.ffmpeg_args.join(" "),
+            "-i /in/talk.mkv -c:v copy -c:a copy -movflags +faststart -y /out/talk.mp4"
+        );
+        assert!(!fix_plan.lossy);
+
+        // Audio problems re-encode only the audio (AAC becomes PCM); bitrate is left alone
+        let mut metadata = create_test_metadata();
+        metadata.audio_sample_rate = 32000;
+        metadata.video_bitrate = 30_000_000;
+        let (remux, _) = plan(&metadata);
+        assert_eq!(remux.video, StreamAction::Copy);
+        assert_eq!(remux.audio, StreamAction::ReEncode);
+        assert_eq!(remux.method(), "copy video, re-encode audio");
+        assert_eq!(
+            remux.re_encoded,
+            vec![ViolationCategory::AudioCodec, ViolationCategory::Audio]
+        );
+        assert_eq!(remux.unchanged, vec![ViolationCategory::Bitrate]);
+
+        // Anything touching the pixels re-encodes the video
+        metadata.resolution = Resolution::new(1000, 700);
+        let (remux, _) = plan(&metadata);
+        assert_eq!(remux.video, StreamAction::ReEncode);
+        assert!(remux.re_encoded.contains(&ViolationCategory::Resolution));
+        assert!(!remux.is_lossless());
     }
 
     #[test]
-    fn test_video_error_from_io() {
-        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "access denied");
-        let video_err: VideoError = io_err.into();
+    fn test_faststart_remux() {
+        let standards = ContentStandards::load_default().unwrap();
+        let engine = ComplianceEngine::with_standards(standards.clone());
+        let mut metadata = create_test_metadata();
+        metadata.audio_codec = "alac".to_string();
+        metadata.faststart = Some(true);
+        assert!(engine.analyze_compliance(&metadata).is_compliant);
 
-        match video_err {
-            VideoError::Io(e) => assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied),
-            _ => panic!("Expected Io variant"),
-        }
+        // An index after the media data is fixed by a stream copy
+        metadata.faststart = Some(false);
+        let result = engine.analyze_compliance(&metadata);
+        assert!(!result.is_compliant);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::Faststart));
+
+        let remux = plan_remux(&result, &metadata, &standards, &ContentType::LiveAction);
+        assert!(remux.is_lossless());
+        assert_eq!(remux.container, "mp4");
+        assert_eq!(remux.lossless, vec![ViolationCategory::Faststart]);
+
+        let plan = FixPlan::new(
+            Path::new("/in/talk.mp4"),
+            Path::new("/out"),
+            &result,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        );
+        assert_eq!(
+            plan.ffmpeg_args.join(" "),
+            "-i /in/talk.mp4 -c:v copy -c:a copy -movflags +faststart -y /out/talk.mp4"
+        );
+        assert!(!plan.lossy);
     }
 
     #[test]
-    fn test_processing_summary_debug() {
-        use std::collections::HashMap;
-
-        let summary = ProcessingSummary {
-            total_videos: 5,
-            total_size: 1000000,
-            total_duration: 300.0,
-            codecs: HashMap::from([("h264".to_string(), 3), ("hevc".to_string(), 2)]),
-            audio_codecs: HashMap::from([("aac".to_string(), 3), ("pcm_s24le".to_string(), 2)]),
-            resolutions: HashMap::from([
-                ("1920x1080".to_string(), 4),
-                ("3840x2160".to_string(), 1),
-            ]),
+    fn test_fix_plan() {
+        let standards = ContentStandards::load_default().unwrap();
+        let engine = ComplianceEngine::with_standards(standards.clone());
+        let plan = |metadata: &VideoMetadata| {
+            let result = engine.analyze_compliance(metadata);
+            FixPlan::new(
+                Path::new("/in/talk.mkv"),
+                Path::new("/out"),
+                &result,
+                metadata,
+                &standards,
+                EncoderBackend::Libx264,
+            )
         };
 
-        let debug_str = format!("{:?}", summary);
-        assert!(debug_str.contains("total_videos: 5"));
-        assert!(debug_str.contains("total_size: 1000000"));
-        assert!(debug_str.contains("total_duration: 300"));
+        // A container-only fix copies both streams and keeps their bitrates
+        let mut metadata = create_test_metadata();
+        metadata.container = "mkv".to_string();
+        metadata.audio_codec = "alac".to_string();
+        let fix_plan = plan(&metadata);
+        assert!(!fix_plan.lossy);
+        assert_eq!(fix_plan.output, Path::new("/out/talk.mp4"));
+        assert_eq!(fix_plan.target.video_codec, "copy");
+        assert_eq!(fix_plan.target.audio_codec, "copy");
+        assert_eq!(fix_plan.target.resolution, Resolution::new(1920, 1080));
+        assert_eq!(fix_plan.estimated_size, 10_320_000 * 120 / 8);
+        let container_fix = fix_plan
+            .fixes
+            .iter()
+            .find(|fix| fix.category == ViolationCategory::Container)
+            .unwrap();
+        assert_eq!(container_fix.remedy, "remux to MP4");
+        assert!(!container_fix.lossy);
+        assert_eq!(fix_plan.ffmpeg_args.last().unwrap(), "/out/talk.mp4");
+
+        // Scaling re-encodes the video, which is lossy
+        metadata.resolution = Resolution::new(1000, 700);
+        let fix_plan = plan(&metadata);
+        assert!(fix_plan.lossy);
+        assert_eq!(fix_plan.target.video_codec, "libx264");
+        assert_ne!(fix_plan.target.resolution, Resolution::new(1000, 700));
+        let resolution_fix = fix_plan
+            .fixes
+            .iter()
+            .find(|fix| fix.category == ViolationCategory::Resolution)
+            .unwrap();
+        assert!(resolution_fix.remedy.starts_with("scale to "));
+        assert!(resolution_fix.lossy);
+
+        let json = serde_json::to_value(&fix_plan).unwrap();
+        assert_eq!(json["target"]["video_codec"], "libx264");
+        assert_eq!(json["remux"]["video"], "re_encode");
+        assert_eq!(json["lossy"], true);
+        assert_eq!(json["estimated_size"], fix_plan.estimated_size);
+        let fixes = json["fixes"].as_array().unwrap();
+        assert_eq!(fixes.len(), fix_plan.fixes.len());
+        let resolution_json = fixes
+            .iter()
+            .find(|fix| fix["category"] == "Resolution")
+            .unwrap();
+        assert_eq!(resolution_json["remedy"], resolution_fix.remedy.as_str());
+        assert_eq!(resolution_json["lossy"], true);
     }
 
     #[test]
-    fn test_check_hardware_support_error_paths() {
-        // This test exercises the error handling paths in check_hardware_support
-        // It will likely fail since we don't have NVIDIA hardware in CI,
-        // but it ensures the error paths are covered
-        let result = check_hardware_support();
+    fn test_dry_run_without_encoder() {
+        let standards = ContentStandards::load_default().unwrap();
+        let engine = ComplianceEngine::with_standards(standards.clone());
+        let unavailable = |_| Err(VideoError::HWAccel("no H.264 encoder".into()));
+        let mut encoder = LazyEncoder::with_probe(EncoderPreference::Auto, unavailable);
+        let plan = |metadata: &VideoMetadata, encoder: &mut LazyEncoder| {
+            let result = engine.analyze_compliance(metadata);
+            plan_file(
+                Path::new("/in/talk.mkv"),
+                Path::new("/out"),
+                &result,
+                metadata,
+                &standards,
+                encoder,
+            )
+        };
 
-        match result {
-            Ok(_) => {
-                // Hardware available - good!
-                println!("Hardware acceleration available");
-            }
-            Err(VideoError::HWAccel(_)) => {
-                // Expected in most CI environments without GPU
-                println!("Hardware acceleration not available (expected in CI)");
-            }
-            Err(VideoError::FFmpeg(_)) => {
-                // FFmpeg not available or doesn't support nvenc
-                println!("FFmpeg error (expected if nvenc not available)");
-            }
-            Err(e) => {
-                panic!("Unexpected error type: {}", e);
-            }
-        }
+        // A remux never needs the encoder
+        let mut metadata = create_test_metadata();
+        metadata.container = "mkv".to_string();
+        let fix_plan = plan(&metadata, &mut encoder);
+        assert_eq!(fix_plan.target.video_codec, "copy");
+
+        // A re-encode is still planned, with the encoder marked unavailable
+        metadata.resolution = Resolution::new(1000, 700);
+        let fix_plan = plan(&metadata, &mut encoder);
+        assert_eq!(fix_plan.remux.video, StreamAction::ReEncode);
+        assert_eq!(fix_plan.target.video_codec, "libx264 (unavailable)");
+        assert!(!fix_plan.fixes.is_empty());
+
+        // A usable encoder replaces the provisional one
+        let mut encoder =
+            LazyEncoder::with_probe(EncoderPreference::Auto, |_| Ok(EncoderBackend::Nvenc));
+        let fix_plan = plan(&metadata, &mut encoder);
+        assert_eq!(fix_plan.target.video_codec, "h264_nvenc");
+        assert!(fix_plan.ffmpeg_args.contains(&"h264_nvenc".to_string()));
     }
 
     #[test]
-    fn test_content_standards_loading() {
-        let standards = ContentStandards::load_default().unwrap();
-
-        // Test video standards
-        assert!(standards
-            .video
-            .preferred_resolutions
-            .contains(&"1920x1080".to_string()));
-        assert!(standards
-            .video
-            .preferred_resolutions
-            .contains(&"1280x720".to_string()));
-        assert!(standards
-            .video
-            .preferred_codecs
-            .contains(&"h264".to_string()));
-        assert!(standards
-            .video
-            .unsupported_containers
-            .contains(&"mkv".to_string()));
+    fn test_profile_frame_rates_enforced() {
+        let registry = profiles::ProfileRegistry::builtin().unwrap();
+        let frame_rate_violations = |standards: ContentStandards, metadata: &VideoMetadata| {
+            ComplianceEngine::with_standards(standards)
+                .analyze_compliance(metadata)
+                .violations
+                .into_iter()
+                .filter(|v| v.category == ViolationCategory::FrameRate)
+                .map(|v| v.description)
+                .collect::<Vec<_>>()
+        };
 
-        // Test audio standards
-        assert!(standards
-            .audio
-            .preferred_codecs
-            .contains(&"pcm".to_string()));
-        assert!(standards
-            .audio
-            .acceptable_codecs
-            .contains(&"aac".to_string()));
+        // 24 fps live action passes the defaults, but the strict screen capture
+        // profile narrows the live action list through preferred_frame_rates
+        let mut metadata = create_test_metadata();
+        metadata.fps = 24.0;
+        let defaults = registry.resolve("default").unwrap();
+        assert!(frame_rate_violations(defaults.clone(), &metadata).is_empty());
+        let strict = registry.resolve("screen-capture-strict").unwrap();
+        assert_eq!(
+            strict.video.frame_rates_for(&ContentType::LiveAction),
+            vec![29.97, 30.0]
+        );
+        assert_eq!(
+            frame_rate_violations(strict, &metadata),
+            ["Frame rate not in preferred list"]
+        );
 
-        // Test quality standards
-        assert!(standards
-            .quality
-            .color_spaces
-            .contains(&"rec709".to_string()));
-        assert!(standards
-            .quality
-            .hdr_restrictions
-            .contains(&"hdr10".to_string()));
+        // A profile allowing only 30 fps rejects 25 fps vertical video
+        let mut publisher = registry.resolve("vertical").unwrap();
+        publisher.video.preferred_frame_rates = vec![30.0];
+        metadata.resolution = Resolution::new(1080, 1920);
+        metadata.fps = 25.0;
+        assert!(frame_rate_violations(defaults, &metadata).is_empty());
+        assert_eq!(
+            frame_rate_violations(publisher.clone(), &metadata),
+            ["Frame rate not in preferred list"]
+        );
+        metadata.fps = 30.0;
+        assert!(frame_rate_violations(publisher, &metadata).is_empty());
     }
 
     #[test]
-    fn test_compliance_engine_creation() {
-        let engine = ComplianceEngine::new();
-        assert!(engine.is_ok());
-
-        let engine = engine.unwrap();
-        let standards = engine.get_standards();
-        assert!(standards
-            .video
-            .preferred_codecs
-            .contains(&"h264".to_string()));
+    fn test_frame_rate_expr() {
+        assert_eq!(frame_rate_expr(29.97), "30000/1001");
+        assert_eq!(frame_rate_expr(23.976), "24000/1001");
+        assert_eq!(frame_rate_expr(30.0), "30");
+        assert_eq!(frame_rate_expr(25.0), "25");
+        assert_eq!(
+            parse_frame_rate(&serde_json::json!("30000/1001")),
+            30000.0 / 1001.0
+        );
+        assert_eq!(parse_frame_rate(&serde_json::json!("0/0")), 0.0);
     }
 
     #[test]
-    fn test_compliance_analysis_compliant_file() {
-        let engine = ComplianceEngine::new().unwrap();
-        let metadata = create_test_metadata(); // h264, 1920x1080, aac, mp4, rec709 - should be compliant
+    fn test_interlaced_video_deinterlaced() {
+        let mut standards = ContentStandards::load_default().unwrap();
+        let mut metadata = create_test_metadata();
+        metadata.field_order = FieldOrder::TopFieldFirst;
+
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        let violation = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::Interlacing)
+            .unwrap();
+        assert_eq!(
+            violation.current_value,
+            "top field first (signalled field order)"
+        );
 
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &metadata,
+            &standards,
+            EncoderBackend::Libx264,
+        )
+        .join(" ");
+        assert!(args.contains("dif=mode=send_frame:parity=tff:deint=all"));
+
+        // idet overrides the stream flags in both directions
+        metadata.interlace = Some(InterlaceAnalysis {
+            progressive: 500,
+            ..Default::default()
+        });
+        let engine = ComplianceEngine::with_standards(standards.clone());
+        assert!(!engine
+            .analyze_compliance(&metadata)
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::Interlacing));
+
+        metadata.field_order = FieldOrder::Progressive;
+        metadata.interlace = Some(InterlaceAnalysis {
+            bff: 450,
+            progressive: 30,
+            undetermined: 20,
+            ..Default::default()
+        });
+        assert_eq!(
+            metadata.interlaced_field_order(),
+            FieldOrder::BottomFieldFirst
+        );
         let result = engine.analyze_compliance(&metadata);
-        assert!(result.is_compliant);
-        assert!(result.score >= 90); // Should have high score for compliant file
-        assert!(result
+        assert!(result.violations.iter().any(|v| {
+            v.category == ViolationCategory::Interlacing
+                && v.current_value
+                    == "bottom field first (idet: TFF 0, BFF 450, progressive 30, undetermined 20)"
+        }));
+
+        standards.quality.require_progressive = false;
+        assert!(!ComplianceEngine::with_standards(standards)
+            .analyze_compliance(&metadata)
             .violations
             .iter()
-            .all(|v| matches!(v.severity, ViolationSeverity::Info)));
+            .any(|v| v.category == ViolationCategory::Interlacing));
     }
 
     #[test]
-    fn test_compliance_analysis_non_compliant_file() {
-        let engine = ComplianceEngine::new().unwrap();
+    fn test_hdr_video_tone_mapped() {
+        let mut standards = ContentStandards::load_default().unwrap();
+        standards.quality.tone_mapping = ToneMapOperator::Mobius;
         let mut metadata = create_test_metadata();
+        metadata.color_space = "bt2020nc".to_string();
+        metadata.color_transfer = "smpte2084".to_string();
+        metadata.color_primaries = "bt2020".to_string();
 
-        // Make it non-compliant
-        metadata.codec = "mpeg4".to_string(); // Not preferred
-        metadata.container = "mkv".to_string(); // Unsupported
-        metadata.color_space = "bt2020".to_string(); // Unsupported HDR
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        assert!(result
+            .recommendations
+            .contains(&"Tone map to SDR (Rec. 709) using the mobius operator".to_string()));
+
+        // The pixels are converted, not just relabelled, before scaling
+        metadata.resolution = Resolution::new(3840, 2160);
+        let result =
+            ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&metadata);
+        for args in [
+            generate_video_fixes(&result, &metadata, &standards, EncoderBackend::Libx264),
+            generate_optimized_video_fixes(
+                &result,
+                &ContentType::LiveAction,
+                &metadata,
+                &standards,
+                EncoderBackend::Libx264,
+            ),
+        ] {
+            let args = args.join(" ");
+            let tonemap = args
+                .find("zscale=tin=smpte2084:min=bt2020nc:pin=bt2020:t=linear")
+                .expect("PQ should be linearized");
+            assert!(args.contains("tonemap=tonemap=mobius:desat=0"));
+            assert!(args.contains("zscale=t=bt709:m=bt709:r=tv:d=error_diffusion"));
+            assert!(tonemap < args.find("scale=1920:1080").unwrap());
+            assert!(args.contains("-color_trc bt709"));
+        }
 
-        let result = engine.analyze_compliance(&metadata);
-        assert!(!result.is_compliant);
-        assert!(result.score < 90); // Should have low score
-        assert!(!result.violations.is_empty());
-        assert!(!result.recommendations.is_empty());
+        // SDR video needs no conversion filters
+        let sdr = create_test_metadata();
+        let result = ComplianceEngine::with_standards(standards.clone()).analyze_compliance(&sdr);
+        assert!(color_conversion_filters(&result, &sdr, &standards).is_empty());
+    }
 
-        // Check for specific violations
+    #[test]
+    fn test_intermediate_codec_formats() {
+        let engine = ComplianceEngine::new().unwrap();
+
+        // ProRes 422 HQ, 10-bit 4:2:2 in QuickTime at its native data rate
+        let mut prores = create_test_metadata();
+        prores.codec = "prores".to_string();
+        prores.profile = "hq".to_string();
+        prores.container = "mov".to_string();
+        prores.audio_codec = "pcm_s24le".to_string();
+        prores.pix_fmt = "yuv422p10le".to_string();
+        prores.chroma_subsampling = "4:2:2".to_string();
+        prores.bit_depth = 10;
+        prores.video_bitrate = 176_000_000;
+        let result = engine.analyze_compliance(&prores);
+        assert!(
+            result.is_compliant,
+            "ProRes 422 should be compliant: {:?}",
+            result.violations
+        );
+
+        // DNxHD SQ, 8-bit 4:2:2 in MXF
+        let mut dnxhd = prores.clone();
+        dnxhd.codec = "dnxhd".to_string();
+        dnxhd.profile = "dnxhd".to_string();
+        dnxhd.container = "mxf".to_string();
+        dnxhd.pix_fmt = "yuv422p".to_string();
+        dnxhd.bit_depth = 8;
+        let result = engine.analyze_compliance(&dnxhd);
+        assert!(
+            result.is_compliant,
+            "DNxHD SQ should be compliant: {:?}",
+            result.violations
+        );
+
+        // ProRes is held to its own rules: QuickTime only, 422 profiles, 10-bit
+        prores.container = "mxf".to_string();
+        prores.profile = "4444".to_string();
+        prores.bit_depth = 12;
+        let result = engine.analyze_compliance(&prores);
+        assert!(!result
+            .violations
+            .iter()
+            .any(|v| v.category == ViolationCategory::VideoCodec));
+
+        let container = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::Container)
+            .expect("ProRes in MXF should be flagged");
+        assert_eq!(container.expected_value, "mov");
         assert!(result
+            .recommendations
+            .contains(&"Rewrap Apple ProRes 422 in MOV".to_string()));
+
+        let profile = result
             .violations
             .iter()
-            .any(|v| matches!(v.category, ViolationCategory::VideoCodec)));
+            .find(|v| v.category == ViolationCategory::Profile)
+            .expect("ProRes 4444 should be flagged");
+        assert_eq!(profile.expected_value, "standard, hq");
+
+        let depth = result
+            .violations
+            .iter()
+            .find(|v| v.category == ViolationCategory::BitDepth)
+            .expect("12-bit ProRes should be flagged");
+        assert_eq!(depth.expected_value, "10-bit");
+
+        // Codecs without a format spec still need to be a preferred codec
+        let mut vp9 = create_test_metadata();
+        vp9.codec = "vp9".to_string();
+        let result = engine.analyze_compliance(&vp9);
         assert!(result
             .violations
             .iter()
-            .any(|v| matches!(v.category, ViolationCategory::Container)));
+            .any(|v| v.category == ViolationCategory::VideoCodec));
+    }
+
+    #[test]
+    fn test_intermediate_codec_reencode() {
+        let standards = ContentStandards::load_default().unwrap();
+        let engine = ComplianceEngine::with_standards(standards.clone());
+
+        // 10-bit ProRes LT: the wrong profile, but otherwise within the format
+        let mut prores = create_test_metadata();
+        prores.codec = "prores".to_string();
+        prores.profile = "lt".to_string();
+        prores.container = "mov".to_string();
+        prores.audio_codec = "pcm_s24le".to_string();
+        prores.pix_fmt = "yuv422p10le".to_string();
+        prores.chroma_subsampling = "4:2:2".to_string();
+        prores.bit_depth = 10;
+        prores.video_bitrate = 102_000_000;
+        let result = engine.analyze_compliance(&prores);
         assert!(result
             .violations
             .iter()
-            .any(|v| matches!(v.category, ViolationCategory::HDR)));
+            .any(|v| v.category == ViolationCategory::Profile));
+
+        // Stays ProRes 422 10-bit instead of becoming 8-bit H.264
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &prores,
+            &standards,
+            EncoderBackend::Libx264,
+        )
+        .join(" ");
+        assert_eq!(args, "-c:v prores_ks -profile:v 2 -pix_fmt yuv422p10le");
+
+        let plan = FixPlan::new(
+            Path::new("/in/master.mov"),
+            Path::new("/out"),
+            &result,
+            &prores,
+            &standards,
+            EncoderBackend::Libx264,
+        );
+        assert_eq!(plan.target.container, "mov");
+        assert_eq!(plan.target.video_codec, "prores_ks");
+        let profile = plan
+            .fixes
+            .iter()
+            .find(|fix| fix.category == ViolationCategory::Profile)
+            .unwrap();
+        assert_eq!(
+            profile.remedy,
+            "re-encode as Apple ProRes 422 (prores_ks, yuv422p10le)"
+        );
+
+        // 4444 sources are brought down to 4:2:2 within the format
+        prores.profile = "4444".to_string();
+        prores.pix_fmt = "yuv444p10le".to_string();
+        prores.chroma_subsampling = "4:4:4".to_string();
+        let result = engine.analyze_compliance(&prores);
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &prores,
+            &standards,
+            EncoderBackend::Libx264,
+        )
+        .join(" ");
+        assert!(args.contains("-c:v prores_ks -profile:v 2 -pix_fmt yuv422p10le"));
+        assert!(!args.contains("libx264"));
+
+        // A format with only classic DNxHD profiles has no encoder without a
+        // fixed bitrate, so the fixer leaves it alone and says so
+        let mut dnxhd_only = standards.clone();
+        dnxhd_only.codec_formats.get_mut("dnxhd").unwrap().profiles = vec!["dnxhd".to_string()];
+        let mut dnxhd = prores.clone();
+        dnxhd.codec = "dnxhd".to_string();
+        dnxhd.profile = "dnxhd".to_string();
+        dnxhd.container = "mxf".to_string();
+        dnxhd.pix_fmt = "yuv422p10le".to_string();
+        dnxhd.chroma_subsampling = "4:2:2".to_string();
+        let result =
+            ComplianceEngine::with_standards(dnxhd_only.clone()).analyze_compliance(&dnxhd);
+        assert!(result.recommendations.iter().any(|rec| rec
+            == "Re-encode Avid DNxHD SQ at 8-bit (not fixed automatically: no ffmpeg encoder for this format)"));
+        let args = generate_optimized_video_fixes(
+            &result,
+            &ContentType::LiveAction,
+            &dnxhd,
+            &dnxhd_only,
+            EncoderBackend::Libx264,
+        );
+        assert_eq!(args, ["-c:v", "copy"]);
+        let plan = FixPlan::new(
+            Path::new("/in/master.mxf"),
+            Path::new("/out"),
+            &result,
+            &dnxhd,
+            &dnxhd_only,
+            EncoderBackend::Libx264,
+        );
+        assert_eq!(plan.remux.video, StreamAction::Copy);
+        assert_eq!(plan.fixes[0].remedy, "left unchanged");
+    }
+
+    #[test]
+    fn test_effective_video_bitrate() {
+        let mut metadata = create_test_metadata();
+        assert_eq!(metadata.effective_video_bitrate(), Some(10_000_000));
+
+        // MKV files usually have no per-stream bitrate
+        metadata.video_bitrate = 0;
+        assert_eq!(metadata.effective_video_bitrate(), Some(10_000_000));
+
+        metadata.bitrate = 0;
+        assert_eq!(metadata.effective_video_bitrate(), None);
     }
 
     #[test]
@@ -2333,6 +6898,7 @@ mod tests {
         let compliant_result = ComplianceResult {
             is_compliant: true,
             score: 100,
+            hdr_format: HdrFormat::Sdr,
             violations: vec![],
             recommendations: vec![],
         };
@@ -2347,6 +6913,7 @@ mod tests {
         let non_compliant_result = ComplianceResult {
             is_compliant: false,
             score: 60,
+            hdr_format: HdrFormat::Sdr,
             violations: vec![ComplianceViolation {
                 severity: ViolationSeverity::Critical,
                 category: ViolationCategory::VideoCodec,
@@ -2392,6 +6959,7 @@ mod tests {
         let result = ComplianceResult {
             is_compliant: false,
             score: 75,
+            hdr_format: HdrFormat::Sdr,
             violations: vec![ComplianceViolation {
                 severity: ViolationSeverity::Warning,
                 category: ViolationCategory::Resolution,
@@ -2412,7 +6980,7 @@ mod tests {
 
         let metadata = VideoMetadata {
             codec: "h264".to_string(),
-            resolution: "1920x1080".to_string(),
+            resolution: Resolution::new(1920, 1080),
             duration: 120.0,
             bitrate: 5000000,
             size: 75000000,
@@ -2423,6 +6991,7 @@ mod tests {
             container: "mp4".to_string(),
             profile: "high".to_string(),
             color_space: "bt709".to_string(),
+            ..Default::default()
         };
 
         summary.add_video(&metadata);
@@ -2441,7 +7010,7 @@ mod tests {
         // Add multiple videos with different codecs
         let metadata1 = VideoMetadata {
             codec: "h264".to_string(),
-            resolution: "1920x1080".to_string(),
+            resolution: Resolution::new(1920, 1080),
             duration: 60.0,
             bitrate: 5000000,
             size: 37500000,
@@ -2452,11 +7021,12 @@ mod tests {
             container: "mp4".to_string(),
             profile: "high".to_string(),
             color_space: "bt709".to_string(),
+            ..Default::default()
         };
 
         let metadata2 = VideoMetadata {
             codec: "h265".to_string(),
-            resolution: "3840x2160".to_string(),
+            resolution: Resolution::new(3840, 2160),
             duration: 180.0,
             bitrate: 15000000,
             size: 337500000,
@@ -2467,6 +7037,7 @@ mod tests {
             container: "mp4".to_string(),
             profile: "main".to_string(),
             color_space: "bt2020".to_string(),
+            ..Default::default()
         };
 
         summary.add_video(&metadata1);
@@ -2510,6 +7081,7 @@ mod tests {
             ContentType::LiveAction,
             ContentType::Animation,
             ContentType::Presentation,
+            ContentType::Vertical,
             ContentType::Unknown,
         ];
 
@@ -2519,6 +7091,7 @@ mod tests {
                 ContentType::LiveAction => assert_eq!(format!("{:?}", ct), "LiveAction"),
                 ContentType::Animation => assert_eq!(format!("{:?}", ct), "Animation"),
                 ContentType::Presentation => assert_eq!(format!("{:?}", ct), "Presentation"),
+                ContentType::Vertical => assert_eq!(format!("{:?}", ct), "Vertical"),
                 ContentType::Unknown => assert_eq!(format!("{:?}", ct), "Unknown"),
             }
         }
@@ -2531,9 +7104,9 @@ mod tests {
         // Test compliant video
         let good_metadata = VideoMetadata {
             codec: "h264".to_string(),
-            resolution: "1920x1080".to_string(),
+            resolution: Resolution::new(1920, 1080),
             duration: 120.0,
-            bitrate: 5000000,
+            bitrate: 12000000,
             size: 75000000,
             fps: 30.0,
             audio_codec: "pcm_s24le".to_string(),
@@ -2542,6 +7115,7 @@ mod tests {
             container: "mp4".to_string(),
             profile: "high".to_string(),
             color_space: "bt709".to_string(),
+            ..Default::default()
         };
 
         let result = engine.analyze_compliance(&good_metadata);
@@ -2551,7 +7125,7 @@ mod tests {
         // Test non-compliant video
         let bad_metadata = VideoMetadata {
             codec: "vp9".to_string(),
-            resolution: "640x480".to_string(),
+            resolution: Resolution::new(640, 480),
             duration: 120.0,
             bitrate: 500000,
             size: 7500000,
@@ -2562,6 +7136,7 @@ mod tests {
             container: "webm".to_string(),
             profile: "0".to_string(),
             color_space: "unknown".to_string(),
+            ..Default::default()
         };
 
         let bad_result = engine.analyze_compliance(&bad_metadata);
@@ -2576,7 +7151,7 @@ mod tests {
         // Edge case: very high frame rate
         let high_fps_metadata = VideoMetadata {
             codec: "h264".to_string(),
-            resolution: "1920x1080".to_string(),
+            resolution: Resolution::new(1920, 1080),
             duration: 60.0,
             bitrate: 8000000,
             size: 60000000,
@@ -2587,6 +7162,7 @@ mod tests {
             container: "mp4".to_string(),
             profile: "high".to_string(),
             color_space: "bt709".to_string(),
+            ..Default::default()
         };
 
         let fps_result = engine.analyze_compliance(&high_fps_metadata);
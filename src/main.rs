@@ -1,10 +1,17 @@
 //src/main.rs
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::*;
+use mp4converter::encoder::{
+    select_encoder, EncoderBackend, EncoderPreference, FfmpegCapabilities,
+};
 use mp4converter::google_drive::GoogleDriveClient;
 use mp4converter::init_logging;
-use mp4converter::{process_directory, ComplianceEngine, VideoError};
+use mp4converter::profiles::ProfileRegistry;
+use mp4converter::{
+    process_directory_with_options, ComplianceEngine, ContentStandards, PlanFormat, ProcessOptions,
+    VideoError,
+};
 use std::path::PathBuf;
 use tracing::{debug, error, info};
 
@@ -34,6 +41,106 @@ pub struct Args {
     /// Path to Google Drive credentials JSON file
     #[arg(long, default_value = "credentials.json")]
     pub credentials: PathBuf,
+
+    /// Content standards file (TOML, YAML or JSON) to use instead of the defaults
+    #[arg(long, value_name = "FILE", conflicts_with = "profile")]
+    pub standards: Option<PathBuf>,
+
+    /// Named standards profile to enforce (see `profiles list`)
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// File with additional `[profiles.<name>]` definitions
+    #[arg(long, value_name = "FILE", global = true)]
+    pub profile_file: Option<PathBuf>,
+
+    /// Decode the first frames through ffmpeg's idet filter to detect interlacing
+    #[arg(long)]
+    pub idet: bool,
+
+    /// Print the fix plan for each non-compliant file (text or json) without converting
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text",
+        conflicts_with = "convert"
+    )]
+    pub dry_run: Option<PlanFormatArg>,
+
+    /// H.264 encoder used for conversion (auto falls back to libx264 without a GPU)
+    #[arg(long, value_enum, default_value_t = EncoderArg::Auto, global = true)]
+    pub encoder: EncoderArg,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Inspect the available standards profiles
+    Profiles {
+        #[command(subcommand)]
+        action: ProfilesAction,
+    },
+    /// Report available ffmpeg encoders, decoders, filters and the selected encoder backend
+    Encoders,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfilesAction {
+    /// List profile names, parents and descriptions
+    List,
+    /// Print the fully resolved standards for a profile
+    Show {
+        /// Profile name
+        name: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = DocumentFormat::Toml)]
+        format: DocumentFormat,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum EncoderArg {
+    Auto,
+    Nvenc,
+    Libx264,
+}
+
+impl From<EncoderArg> for EncoderPreference {
+    fn from(arg: EncoderArg) -> Self {
+        match arg {
+            EncoderArg::Auto => EncoderPreference::Auto,
+            EncoderArg::Nvenc => EncoderPreference::Nvenc,
+            EncoderArg::Libx264 => EncoderPreference::Libx264,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum PlanFormatArg {
+    Text,
+    Json,
+}
+
+impl From<PlanFormatArg> for PlanFormat {
+    fn from(arg: PlanFormatArg) -> Self {
+        match arg {
+            PlanFormatArg::Text => PlanFormat::Text,
+            PlanFormatArg::Json => PlanFormat::Json,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DocumentFormat {
+    Toml,
+    Yaml,
+    Json,
 }
 
 #[tokio::main]
@@ -44,6 +151,17 @@ async fn main() -> Result<(), VideoError> {
     let args = Args::parse();
     debug!(?args, "parsed arguments");
 
+    if let Some(command) = &args.command {
+        let result = match command {
+            Commands::Profiles { action } => run_profiles_command(&args, action),
+            Commands::Encoders => run_encoders_command(&args),
+        };
+        return result.map_err(|e| {
+            eprintln!("\n{} {}", "Error:".red().bold(), e);
+            e
+        });
+    }
+
     // Validate arguments
     if !args.drive && args.dir.is_none() {
         eprintln!(
@@ -53,9 +171,18 @@ async fn main() -> Result<(), VideoError> {
         std::process::exit(1);
     }
 
+    let standards = match load_standards(&args) {
+        Ok(standards) => standards,
+        Err(e) => {
+            error!(?e, "failed to load standards");
+            eprintln!("\n{} {}", "Error:".red().bold(), e);
+            return Err(e);
+        }
+    };
+
     if args.drive {
         // Google Drive mode
-        match process_google_drive(&args).await {
+        match process_google_drive(&args, standards).await {
             Ok(_) => {
                 info!("Google Drive processing completed");
                 println!(
@@ -74,10 +201,22 @@ async fn main() -> Result<(), VideoError> {
         }
     } else if let Some(dir) = &args.dir {
         // Local directory mode
-        match process_directory(dir, args.convert, args.verbose, args.compliance) {
+        let options = ProcessOptions {
+            convert: args.convert,
+            verbose: args.verbose,
+            compliance: args.compliance,
+            standards,
+            encoder: args.encoder.into(),
+            detect_interlace: args.idet,
+            dry_run: args.dry_run.map(Into::into),
+        };
+        match process_directory_with_options(dir, options) {
             Ok(_) => {
                 info!("processing completed");
-                println!("\n{}", "Processing completed successfully!".green().bold());
+                // Keep JSON plans parseable
+                if args.dry_run != Some(PlanFormatArg::Json) {
+                    println!("\n{}", "Processing completed successfully!".green().bold());
+                }
                 Ok(())
             }
             Err(e) => {
@@ -91,7 +230,113 @@ async fn main() -> Result<(), VideoError> {
     }
 }
 
-async fn process_google_drive(args: &Args) -> Result<(), VideoError> {
+/// Built-in profiles plus any defined in --profile-file
+fn build_profile_registry(args: &Args) -> Result<ProfileRegistry, VideoError> {
+    let mut registry = ProfileRegistry::builtin()?;
+    if let Some(path) = &args.profile_file {
+        registry.load_from_path(path)?;
+    }
+    Ok(registry)
+}
+
+/// Load the standards selected with --standards or --profile, if any
+fn load_standards(args: &Args) -> Result<Option<ContentStandards>, VideoError> {
+    if let Some(path) = &args.standards {
+        return ContentStandards::load_from_path(path).map(Some);
+    }
+
+    match &args.profile {
+        Some(name) => build_profile_registry(args)?.resolve(name).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn run_profiles_command(args: &Args, action: &ProfilesAction) -> Result<(), VideoError> {
+    let registry = build_profile_registry(args)?;
+
+    match action {
+        ProfilesAction::List => {
+            println!("{}", "📋 Standards Profiles".bright_blue().bold());
+            println!("{}", "=".repeat(60).bright_blue());
+            for profile in registry.profiles() {
+                let parent = profile
+                    .extends
+                    .as_deref()
+                    .map(|p| format!(" (extends {})", p))
+                    .unwrap_or_default();
+                println!("{}{}", profile.name.bold(), parent.dimmed());
+                println!("   {}", profile.description);
+            }
+        }
+        ProfilesAction::Show { name, format } => {
+            let standards = registry.resolve(name)?;
+            let lineage: Vec<String> = registry
+                .lineage(name)?
+                .iter()
+                .map(|p| p.name.clone())
+                .collect();
+
+            let document = match format {
+                DocumentFormat::Toml => toml::to_string_pretty(&standards)
+                    .map_err(|e| VideoError::Standards(e.to_string()))?,
+                DocumentFormat::Yaml => serde_norway::to_string(&standards)
+                    .map_err(|e| VideoError::Standards(e.to_string()))?,
+                DocumentFormat::Json => serde_json::to_string_pretty(&standards)
+                    .map_err(|e| VideoError::Standards(e.to_string()))?,
+            };
+
+            // JSON has no comments, so only annotate TOML and YAML output
+            if *format != DocumentFormat::Json {
+                println!("# Profile: {}", name);
+                println!("# Resolved from: {}", lineage.join(" -> "));
+            }
+            println!("{}", document);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_encoders_command(args: &Args) -> Result<(), VideoError> {
+    let capabilities = FfmpegCapabilities::probe()?;
+    capabilities.display();
+
+    println!("\n{}", "🎬 Encoder Backends".bright_blue().bold());
+    for backend in [EncoderBackend::Nvenc, EncoderBackend::Libx264] {
+        match backend.check_available() {
+            Ok(()) => println!("  {} {}", "✅".green(), backend),
+            Err(e) => println!(
+                "  {} {} {}",
+                "❌".red(),
+                backend,
+                format!("({})", e).dimmed()
+            ),
+        }
+    }
+
+    let preference = EncoderPreference::from(args.encoder);
+    match select_encoder(preference) {
+        Ok(backend) => println!(
+            "\n{} --encoder {:?} selects {}",
+            "➡️".blue(),
+            args.encoder,
+            backend.to_string().bold()
+        ),
+        Err(e) => println!(
+            "\n{} --encoder {:?} cannot convert: {}",
+            "⚠️".yellow(),
+            args.encoder,
+            e
+        ),
+    }
+
+    Ok(())
+}
+
+async fn process_google_drive(
+    args: &Args,
+    standards: Option<ContentStandards>,
+) -> Result<(), VideoError> {
     info!("Starting Google Drive compliance analysis");
 
     // Initialize Google Drive client
@@ -100,7 +345,10 @@ async fn process_google_drive(args: &Args) -> Result<(), VideoError> {
 
     // Initialize compliance engine
     println!("{}", "📋 Loading content standards...".blue().bold());
-    let compliance_engine = ComplianceEngine::new()?;
+    let compliance_engine = match standards {
+        Some(standards) => ComplianceEngine::with_standards(standards),
+        None => ComplianceEngine::new()?,
+    };
 
     // Perform compliance audit
     println!(
@@ -124,6 +372,8 @@ async fn process_google_drive(args: &Args) -> Result<(), VideoError> {
 mod tests {
     use super::*;
     use clap::Parser;
+    use mp4converter::process_directory;
+    use mp4converter::resolution::Resolution;
     use std::fs;
     use tempfile::tempdir;
 
@@ -173,6 +423,13 @@ mod tests {
             compliance: true,
             drive: false,
             credentials: PathBuf::from("credentials.json"),
+            standards: None,
+            profile: None,
+            profile_file: None,
+            idet: false,
+            dry_run: None,
+            encoder: EncoderArg::Auto,
+            command: None,
         };
 
         let debug_str = format!("{:?}", args);
@@ -267,6 +524,144 @@ mod tests {
         assert!(args.verbose);
     }
 
+    #[test]
+    fn test_standards_arg() {
+        let args = Args::try_parse_from([
+            "mp4converter",
+            "--dir",
+            "/test/path",
+            "--standards",
+            "publisher.toml",
+        ])
+        .unwrap();
+
+        assert_eq!(args.standards, Some(PathBuf::from("publisher.toml")));
+        assert!(load_standards(&args).is_err()); // File does not exist
+    }
+
+    #[test]
+    fn test_encoder_arg() {
+        let args = Args::try_parse_from(["mp4converter", "--dir", "/test/path"]).unwrap();
+        assert_eq!(args.encoder, EncoderArg::Auto);
+
+        let args = Args::try_parse_from([
+            "mp4converter",
+            "--dir",
+            "/test/path",
+            "--convert",
+            "--encoder",
+            "libx264",
+        ])
+        .unwrap();
+        assert_eq!(
+            EncoderPreference::from(args.encoder),
+            EncoderPreference::Libx264
+        );
+
+        assert!(Args::try_parse_from([
+            "mp4converter",
+            "--dir",
+            "/test/path",
+            "--encoder",
+            "vaapi"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_dry_run_arg() {
+        let args = Args::try_parse_from(["mp4converter", "--dir", "/test/path"]).unwrap();
+        assert_eq!(args.dry_run, None);
+
+        let args =
+            Args::try_parse_from(["mp4converter", "--dir", "/test/path", "--dry-run"]).unwrap();
+        assert_eq!(args.dry_run, Some(PlanFormatArg::Text));
+
+        let args = Args::try_parse_from(["mp4converter", "--dir", "/test/path", "--dry-run=json"])
+            .unwrap();
+        assert_eq!(args.dry_run.map(PlanFormat::from), Some(PlanFormat::Json));
+
+        // A dry run never converts
+        assert!(Args::try_parse_from([
+            "mp4converter",
+            "--dir",
+            "/test/path",
+            "--dry-run",
+            "--convert"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_profile_args() {
+        let args = Args::try_parse_from([
+            "mp4converter",
+            "--dir",
+            "/test/path",
+            "--profile",
+            "vertical",
+        ])
+        .unwrap();
+        assert_eq!(args.profile.as_deref(), Some("vertical"));
+
+        let standards = load_standards(&args).unwrap().unwrap();
+        assert!(standards
+            .video
+            .preferred_resolutions
+            .contains(&Resolution::new(1080, 1920)));
+
+        // --standards and --profile are mutually exclusive
+        let result = Args::try_parse_from([
+            "mp4converter",
+            "--standards",
+            "publisher.toml",
+            "--profile",
+            "vertical",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_encoders_subcommand() {
+        let args = Args::try_parse_from(["mp4converter", "encoders"]).unwrap();
+        assert!(matches!(args.command, Some(Commands::Encoders)));
+
+        let args =
+            Args::try_parse_from(["mp4converter", "encoders", "--encoder", "libx264"]).unwrap();
+        assert_eq!(args.encoder, EncoderArg::Libx264);
+    }
+
+    #[test]
+    fn test_profiles_subcommand() {
+        let args = Args::try_parse_from(["mp4converter", "profiles", "list"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Commands::Profiles {
+                action: ProfilesAction::List
+            })
+        ));
+
+        let args = Args::try_parse_from([
+            "mp4converter",
+            "profiles",
+            "show",
+            "mezzanine-prores",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        match &args.command {
+            Some(Commands::Profiles { action }) => {
+                assert!(matches!(
+                    action,
+                    ProfilesAction::Show { name, format: DocumentFormat::Json } if name == "mezzanine-prores"
+                ));
+                assert!(run_profiles_command(&args, action).is_ok());
+            }
+            _ => panic!("Expected profiles subcommand"),
+        }
+    }
+
     #[test]
     fn test_pathbuf_handling() {
         let temp_dir = tempdir().unwrap();
@@ -279,6 +674,13 @@ mod tests {
             compliance: false,
             drive: false,
             credentials: PathBuf::from("credentials.json"),
+            standards: None,
+            profile: None,
+            profile_file: None,
+            idet: false,
+            dry_run: None,
+            encoder: EncoderArg::Auto,
+            command: None,
         };
 
         assert_eq!(args.dir, Some(path.to_path_buf()));
@@ -0,0 +1,366 @@
+//! Named content standards profiles
+//!
+//! This module provides:
+//! - A registry of built-in profiles for common delivery targets
+//! - Profile inheritance via `extends`, overriding individual standards fields
+//! - Loading additional profiles from TOML/YAML/JSON files
+//!
+//! Overrides follow JSON merge-patch semantics: tables are merged key by key,
+//! lists and scalar values replace the inherited value, and `null` (in YAML or
+//! JSON files) removes an inherited key such as a bitrate range.
+
+use crate::{read_document, ContentStandards, VideoError};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{debug, info, instrument};
+
+/// Name of the profile that mirrors `ContentStandards::load_default()`
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Clone)]
+pub struct StandardsProfile {
+    pub name: String,
+    pub description: String,
+    pub extends: Option<String>,
+    /// Partial `ContentStandards` document applied on top of the parent
+    pub overrides: Value,
+}
+
+/// On-disk form of a profile inside a `[profiles.<name>]` table
+#[derive(Debug, Deserialize)]
+struct ProfileDefinition {
+    #[serde(default)]
+    description: String,
+    extends: Option<String>,
+    #[serde(flatten)]
+    overrides: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    profiles: BTreeMap<String, ProfileDefinition>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProfileRegistry {
+    profiles: BTreeMap<String, StandardsProfile>,
+}
+
+impl ProfileRegistry {
+    /// Registry containing the built-in profiles
+    pub fn builtin() -> Result<Self, VideoError> {
+        let defaults = serde_json::to_value(ContentStandards::load_default()?)
+            .map_err(|e| VideoError::Standards(e.to_string()))?;
+
+        let mut registry = ProfileRegistry {
+            profiles: BTreeMap::new(),
+        };
+
+        registry.insert(StandardsProfile {
+            name: DEFAULT_PROFILE.to_string(),
+            description: "Bundled content delivery specification".to_string(),
+            extends: None,
+            overrides: defaults,
+        });
+
+        registry.insert(StandardsProfile {
+            name: "vertical".to_string(),
+            description: "9:16 vertical video for mobile platforms".to_string(),
+            extends: Some(DEFAULT_PROFILE.to_string()),
+            overrides: json!({
+                "video": {
+                    "preferred_resolutions": ["720x1280", "1080x1920"],
+                    "acceptable_resolutions": ["2160x3840"],
+                    "preferred_frame_rates": [23.976, 24.0, 25.0, 29.97, 30.0],
+                }
+            }),
+        });
+
+        registry.insert(StandardsProfile {
+            name: "mezzanine-prores".to_string(),
+            description: "Apple ProRes 422 10-bit 4:2:2 mezzanine masters in QuickTime".to_string(),
+            extends: Some(DEFAULT_PROFILE.to_string()),
+            overrides: json!({
+                "video": {
+                    "preferred_codecs": ["prores"],
+                    "bitrate_ranges": { "screen_capture": null, "live_action": null },
+                    "containers": ["mov"],
+                    "unsupported_containers": ["mkv", "mp4", "avi"],
+                },
+                "audio": {
+                    "preferred_codecs": ["pcm"],
+                    "acceptable_codecs": [],
+                    "bit_depths": [24],
+                },
+                // Profile, chroma and bit depth come from the ProRes format rules
+                "codec_formats": { "dnxhd": null }
+            }),
+        });
+
+        registry.insert(StandardsProfile {
+            name: "screen-capture-strict".to_string(),
+            description: "Screen recordings only: landscape, 15/29.97/30 fps, no AAC".to_string(),
+            extends: Some(DEFAULT_PROFILE.to_string()),
+            overrides: json!({
+                "video": {
+                    "preferred_resolutions": [
+                        "1280x720", "1360x768", "1280x800", "1600x900", "1920x1080"
+                    ],
+                    "acceptable_resolutions": [],
+                    "preferred_frame_rates": [15.0, 29.97, 30.0],
+                    "bitrate_ranges": { "live_action": null },
+                },
+                "audio": {
+                    "acceptable_codecs": [],
+                }
+            }),
+        });
+
+        Ok(registry)
+    }
+
+    /// Add or replace profiles from a file with a top-level `profiles` table
+    #[instrument(skip(self))]
+    pub fn load_from_path(&mut self, path: &Path) -> Result<(), VideoError> {
+        let file: ProfileFile = read_document(path)?;
+
+        for (name, definition) in file.profiles {
+            info!("Registering profile '{}' from {}", name, path.display());
+            self.insert(StandardsProfile {
+                name,
+                description: definition.description,
+                extends: definition.extends,
+                overrides: Value::Object(definition.overrides),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, profile: StandardsProfile) {
+        self.profiles.insert(profile.name.clone(), profile);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&StandardsProfile> {
+        self.profiles.get(name)
+    }
+
+    /// Profiles in name order
+    pub fn profiles(&self) -> impl Iterator<Item = &StandardsProfile> {
+        self.profiles.values()
+    }
+
+    /// Inheritance chain for a profile, starting at its root ancestor
+    pub fn lineage(&self, name: &str) -> Result<Vec<&StandardsProfile>, VideoError> {
+        let mut chain: Vec<&StandardsProfile> = Vec::new();
+        let mut current = Some(name);
+
+        while let Some(profile_name) = current {
+            if chain.iter().any(|p| p.name == profile_name) {
+                let mut names: Vec<&str> = chain.iter().rev().map(|p| p.name.as_str()).collect();
+                names.push(profile_name);
+                return Err(VideoError::Standards(format!(
+                    "profile '{}': inheritance cycle {}",
+                    name,
+                    names.join(" <- ")
+                )));
+            }
+
+            let profile = self.get(profile_name).ok_or_else(|| {
+                let message = match chain.last() {
+                    Some(child) => format!(
+                        "profile '{}' extends unknown profile '{}'",
+                        child.name, profile_name
+                    ),
+                    None => format!(
+                        "unknown profile '{}' (available: {})",
+                        profile_name,
+                        self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+                    ),
+                };
+                VideoError::Standards(message)
+            })?;
+
+            chain.push(profile);
+            current = profile.extends.as_deref();
+        }
+
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Fully resolved and validated standards for a profile
+    pub fn resolve(&self, name: &str) -> Result<ContentStandards, VideoError> {
+        let mut document = Value::Object(Map::new());
+        for profile in self.lineage(name)? {
+            debug!("Applying profile '{}'", profile.name);
+            merge_patch(&mut document, &profile.overrides);
+        }
+
+        let standards: ContentStandards =
+            serde_path_to_error::deserialize(document).map_err(|e| {
+                VideoError::Standards(format!("profile '{}': {}: {}", name, e.path(), e.inner()))
+            })?;
+
+        standards
+            .validate()
+            .map_err(|e| VideoError::Standards(format!("profile '{}': {}", name, e)))?;
+
+        Ok(standards)
+    }
+}
+
+/// Apply a JSON merge patch (RFC 7386) to `target`
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let target = target.as_object_mut().expect("target is an object");
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolution::Resolution;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_builtin_profiles_resolve() {
+        let registry = ProfileRegistry::builtin().unwrap();
+
+        let names: Vec<&str> = registry.profiles().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "default",
+                "mezzanine-prores",
+                "screen-capture-strict",
+                "vertical"
+            ]
+        );
+
+        for name in names {
+            let standards = registry.resolve(name).unwrap();
+            assert!(standards.validate().is_ok(), "Failed for {}", name);
+        }
+    }
+
+    #[test]
+    fn test_default_profile_matches_default_standards() {
+        let registry = ProfileRegistry::builtin().unwrap();
+        let resolved = registry.resolve(DEFAULT_PROFILE).unwrap();
+        let defaults = ContentStandards::load_default().unwrap();
+
+        assert_eq!(
+            serde_json::to_value(resolved).unwrap(),
+            serde_json::to_value(defaults).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_profile_overrides_individual_fields() {
+        let registry = ProfileRegistry::builtin().unwrap();
+        let strict = registry.resolve("screen-capture-strict").unwrap();
+
+        assert_eq!(strict.video.preferred_frame_rates, vec![15.0, 29.97, 30.0]);
+        assert!(strict.audio.acceptable_codecs.is_empty());
+        // Removed by a null override, sibling range is inherited
+        assert!(!strict.video.bitrate_ranges.contains_key("live_action"));
+        assert!(strict.video.bitrate_ranges.contains_key("screen_capture"));
+        // Untouched sections are inherited from default
+        assert_eq!(strict.audio.sample_rates, vec![44100, 48000]);
+        assert_eq!(strict.quality.keyframe_interval_min, 2);
+    }
+
+    #[test]
+    fn test_profiles_from_file_extend_builtins() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("publishers.toml");
+        std::fs::write(
+            &path,
+            r#"
+[profiles.publisher-a]
+description = "Publisher A vertical deliveries"
+extends = "vertical"
+
+[profiles.publisher-a.video]
+preferred_frame_rates = [30.0]
+"#,
+        )
+        .unwrap();
+
+        let mut registry = ProfileRegistry::builtin().unwrap();
+        registry.load_from_path(&path).unwrap();
+
+        let lineage: Vec<&str> = registry
+            .lineage("publisher-a")
+            .unwrap()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(lineage, ["default", "vertical", "publisher-a"]);
+
+        let standards = registry.resolve("publisher-a").unwrap();
+        assert_eq!(standards.video.preferred_frame_rates, vec![30.0]);
+        assert!(standards
+            .video
+            .preferred_resolutions
+            .contains(&Resolution::new(1080, 1920)));
+    }
+
+    #[test]
+    fn test_profile_resolution_errors() {
+        let mut registry = ProfileRegistry::builtin().unwrap();
+
+        let err = registry.resolve("missing").unwrap_err();
+        assert!(err.to_string().contains("unknown profile 'missing'"));
+
+        registry.insert(StandardsProfile {
+            name: "orphan".to_string(),
+            description: String::new(),
+            extends: Some("nowhere".to_string()),
+            overrides: json!({}),
+        });
+        let err = registry.resolve("orphan").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("profile 'orphan' extends unknown profile 'nowhere'"));
+
+        for (name, parent) in [("a", "b"), ("b", "a")] {
+            registry.insert(StandardsProfile {
+                name: name.to_string(),
+                description: String::new(),
+                extends: Some(parent.to_string()),
+                overrides: json!({}),
+            });
+        }
+        let err = registry.resolve("a").unwrap_err();
+        assert!(err.to_string().contains("inheritance cycle"));
+
+        registry.insert(StandardsProfile {
+            name: "typo".to_string(),
+            description: String::new(),
+            extends: Some(DEFAULT_PROFILE.to_string()),
+            overrides: json!({ "video": { "frame_rates": [30.0] } }),
+        });
+        let err = registry.resolve("typo").unwrap_err();
+        assert!(matches!(err, VideoError::Standards(_)));
+        assert!(err.to_string().contains("frame_rates"));
+    }
+}
@@ -0,0 +1,148 @@
+//! QuickTime/MP4 atom layout
+//!
+//! This module provides:
+//! - A reader for the top-level atoms (boxes) of an MP4 or MOV file
+//! - Detection of files whose `moov` index follows the media data, which
+//!   players have to download in full before playback can start
+//!
+//! Only the 8 or 16 byte atom headers are read; the scan seeks past the
+//! media data, so it costs a handful of reads however large the file is.
+
+use crate::VideoError;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use tracing::{debug, instrument};
+
+/// Type and byte range of one top-level atom
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Atom {
+    /// Four-character type, e.g. "ftyp", "moov" or "mdat"
+    pub kind: String,
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// Read the headers of the top-level atoms in file order. A truncated last
+/// atom is still listed; an atom smaller than its own header, or one whose
+/// size runs past the largest offset, is an error.
+pub fn read_top_level_atoms<R: Read + Seek>(reader: &mut R) -> Result<Vec<Atom>, VideoError> {
+    let length = reader.seek(SeekFrom::End(0))?;
+    let mut atoms = Vec::new();
+    let mut offset: u64 = 0;
+
+    while length.saturating_sub(offset) >= 8 {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        let kind = String::from_utf8_lossy(&header[4..]).to_string();
+
+        let (size, header_size) = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+            // 64-bit size follows the type
+            1 => {
+                let mut large_size = [0u8; 8];
+                reader.read_exact(&mut large_size)?;
+                (u64::from_be_bytes(large_size), 16)
+            }
+            // Extends to the end of the file
+            0 => (length - offset, 8),
+            size => (size as u64, 8),
+        };
+        if size < header_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("atom '{}' at offset {} has size {}", kind, offset, size),
+            )
+            .into());
+        }
+
+        let next = match offset.checked_add(size) {
+            Some(next) if next > offset => next,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "atom '{}' at offset {} overflows with size {}",
+                        kind, offset, size
+                    ),
+                )
+                .into())
+            }
+        };
+        atoms.push(Atom { kind, offset, size });
+        offset = next;
+    }
+    Ok(atoms)
+}
+
+/// Whether the `moov` index precedes the first `mdat`; `None` when either is missing
+pub fn moov_before_mdat(atoms: &[Atom]) -> Option<bool> {
+    let position = |kind: &str| atoms.iter().position(|atom| atom.kind == kind);
+    Some(position("moov")? < position("mdat")?)
+}
+
+/// Read the atom layout of an MP4 or MOV file and report whether it is
+/// optimized for streaming (`-movflags +faststart`)
+#[instrument]
+pub fn probe_faststart(path: &Path) -> Result<Option<bool>, VideoError> {
+    let atoms = read_top_level_atoms(&mut File::open(path)?)?;
+    debug!(?atoms, "top-level atoms");
+    Ok(moov_before_mdat(&atoms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// An atom with a 32-bit size header and `payload` zero bytes
+    fn atom(kind: &str, payload: usize) -> Vec<u8> {
+        let mut bytes = ((payload + 8) as u32).to_be_bytes().to_vec();
+        bytes.extend(kind.as_bytes());
+        bytes.extend(vec![0; payload]);
+        bytes
+    }
+
+    #[test]
+    fn test_top_level_atom_order() {
+        let mut file = [atom("ftyp", 16), atom("mdat", 1000), atom("moov", 200)].concat();
+        let atoms = read_top_level_atoms(&mut Cursor::new(&file)).unwrap();
+        let kinds: Vec<&str> = atoms.iter().map(|a| a.kind.as_str()).collect();
+        assert_eq!(kinds, ["ftyp", "mdat", "moov"]);
+        assert_eq!(atoms[2].offset, 24 + 1008);
+        assert_eq!(moov_before_mdat(&atoms), Some(false));
+
+        // 64-bit size
+        file = [atom("ftyp", 16), atom("moov", 200)].concat();
+        file.extend(1u32.to_be_bytes());
+        file.extend(b"mdat");
+        file.extend(40u64.to_be_bytes());
+        file.extend([0; 24]);
+        let atoms = read_top_level_atoms(&mut Cursor::new(&file)).unwrap();
+        assert_eq!(atoms[2].size, 40);
+        assert_eq!(moov_before_mdat(&atoms), Some(true));
+
+        // Size 0 runs to the end of the file
+        file = [
+            atom("moov", 8),
+            vec![0, 0, 0, 0],
+            b"mdat".to_vec(),
+            vec![0; 100],
+        ]
+        .concat();
+        let atoms = read_top_level_atoms(&mut Cursor::new(&file)).unwrap();
+        assert_eq!(atoms[1].size, 108);
+
+        assert_eq!(moov_before_mdat(&atoms[..1]), None);
+        let corrupt = [4u32.to_be_bytes().to_vec(), b"free".to_vec()].concat();
+        assert!(read_top_level_atoms(&mut Cursor::new(&corrupt)).is_err());
+
+        // A 64-bit size that wraps the offset is rejected instead of overflowing
+        let mut corrupt = atom("ftyp", 16);
+        corrupt.extend(1u32.to_be_bytes());
+        corrupt.extend(b"mdat");
+        corrupt.extend(u64::MAX.to_be_bytes());
+        let error = read_top_level_atoms(&mut Cursor::new(&corrupt)).unwrap_err();
+        assert!(matches!(error, VideoError::Io(e) if e.kind() == io::ErrorKind::InvalidData));
+    }
+}
@@ -0,0 +1,385 @@
+//! Typed video resolutions
+//!
+//! This module provides:
+//! - `Resolution`, parsed from and serialized as "WIDTHxHEIGHT" strings
+//! - Orientation and aspect ratio helpers
+//! - Nearest-standard lookup against a list of allowed resolutions
+//! - Target selection and `scale`/`pad`/`crop` filter chains for a `ScalingPolicy`
+//!
+//! Standards files keep listing resolutions as plain strings such as
+//! "1920x1080"; they are validated when the file is parsed.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Landscape,
+    Portrait,
+    Square,
+}
+
+/// How a source is fitted into a target resolution with a different aspect ratio
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalingPolicy {
+    /// Scale down to fit and letterbox/pillarbox with black bars
+    #[default]
+    Pad,
+    /// Scale up to fill the target and crop the overflow around the center
+    Crop,
+    /// Scale down to fit without bars; the output may be smaller than the target
+    Fit,
+}
+
+impl fmt::Display for ScalingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ScalingPolicy::Pad => "letterbox pad",
+            ScalingPolicy::Crop => "center crop",
+            ScalingPolicy::Fit => "scale to fit",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Aspect ratios within 1% count as equal, e.g. 1366x768 and 16:9
+const ASPECT_TOLERANCE: f64 = 0.01;
+
+impl Resolution {
+    pub const HD: Resolution = Resolution::new(1280, 720);
+    pub const FULL_HD: Resolution = Resolution::new(1920, 1080);
+
+    pub const fn new(width: u32, height: u32) -> Self {
+        Resolution { width, height }
+    }
+
+    /// False for the 0x0 placeholder used when ffprobe reports no dimensions
+    pub fn is_known(&self) -> bool {
+        self.width > 0 && self.height > 0
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        match self.width.cmp(&self.height) {
+            std::cmp::Ordering::Greater => Orientation::Landscape,
+            std::cmp::Ordering::Less => Orientation::Portrait,
+            std::cmp::Ordering::Equal => Orientation::Square,
+        }
+    }
+
+    /// Width over height, e.g. 1.778 for 1920x1080
+    pub fn aspect_ratio(&self) -> f64 {
+        if self.height == 0 {
+            return 0.0;
+        }
+        self.width as f64 / self.height as f64
+    }
+
+    pub fn pixel_count(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// The same resolution in landscape orientation, e.g. 1080x1920 -> 1920x1080
+    pub fn landscape(&self) -> Resolution {
+        if self.height > self.width {
+            Resolution::new(self.height, self.width)
+        } else {
+            *self
+        }
+    }
+
+    /// Same aspect ratio within `ASPECT_TOLERANCE`
+    pub fn same_aspect(&self, other: &Resolution) -> bool {
+        self.aspect_distance(other) <= ASPECT_TOLERANCE
+    }
+
+    fn aspect_distance(&self, other: &Resolution) -> f64 {
+        (other.aspect_ratio() / self.aspect_ratio()).ln().abs()
+    }
+
+    /// Closest candidate, preferring the same orientation, then the same
+    /// aspect ratio, then the smallest change in pixel count
+    pub fn nearest<'a, I>(&self, candidates: I) -> Option<Resolution>
+    where
+        I: IntoIterator<Item = &'a Resolution>,
+    {
+        let candidates: Vec<Resolution> = candidates
+            .into_iter()
+            .copied()
+            .filter(Resolution::is_known)
+            .collect();
+        let orientation = self.orientation();
+        let same_orientation = candidates.iter().any(|c| c.orientation() == orientation);
+
+        candidates
+            .into_iter()
+            .filter(|c| !same_orientation || c.orientation() == orientation)
+            .map(|c| {
+                let pixel_distance = (c.pixel_count() as f64 / self.pixel_count().max(1) as f64)
+                    .ln()
+                    .abs();
+                (
+                    c,
+                    !self.same_aspect(&c),
+                    pixel_distance,
+                    self.aspect_distance(&c),
+                )
+            })
+            .min_by(|a, b| {
+                a.1.cmp(&b.1)
+                    .then(a.2.total_cmp(&b.2))
+                    .then(a.3.total_cmp(&b.3))
+            })
+            .map(|(c, ..)| c)
+    }
+
+    /// Dimensions in ffmpeg filter syntax, e.g. "1920:1080"
+    pub fn to_filter_arg(&self) -> String {
+        format!("{}:{}", self.width, self.height)
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+// Logged metadata stays readable: `Resolution(1920x1080)`
+impl fmt::Debug for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Resolution({})", self)
+    }
+}
+
+impl FromStr for Resolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not a WIDTHxHEIGHT resolution", s);
+        let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+        let width: u32 = width.trim().parse().map_err(|_| invalid())?;
+        let height: u32 = height.trim().parse().map_err(|_| invalid())?;
+        if width == 0 || height == 0 {
+            return Err(invalid());
+        }
+        Ok(Resolution { width, height })
+    }
+}
+
+impl Serialize for Resolution {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Resolution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Pick the scaling target for `source`: the nearest preferred resolution with the
+/// same orientation and aspect ratio, then such an acceptable resolution, and
+/// otherwise the nearest preferred resolution (padded or cropped to fit).
+/// Portrait sources only ever get portrait targets, so vertical footage is
+/// never squashed into a landscape frame.
+pub fn select_target(
+    source: Resolution,
+    preferred: &[Resolution],
+    acceptable: &[Resolution],
+) -> Option<Resolution> {
+    let matching = |list: &[Resolution]| {
+        let matches: Vec<Resolution> = list
+            .iter()
+            .copied()
+            .filter(|c| c.orientation() == source.orientation() && source.same_aspect(c))
+            .collect();
+        source.nearest(&matches)
+    };
+
+    matching(preferred)
+        .or_else(|| matching(acceptable))
+        .or_else(|| source.nearest(preferred))
+        .filter(|target| {
+            source.orientation() != Orientation::Portrait
+                || target.orientation() == Orientation::Portrait
+        })
+}
+
+/// ffmpeg video filters that bring `source` to `target` under `policy`;
+/// empty when no scaling is needed
+pub fn scale_filters(source: Resolution, target: Resolution, policy: ScalingPolicy) -> Vec<String> {
+    if source == target {
+        return Vec::new();
+    }
+    let size = target.to_filter_arg();
+    if source.same_aspect(&target) {
+        return vec![format!("scale={}", size)];
+    }
+
+    match policy {
+        ScalingPolicy::Pad => vec![
+            format!(
+                "scale={}:force_original_aspect_ratio=decrease:force_divisible_by=2",
+                size
+            ),
+            format!("pad={}:(ow-iw)/2:(oh-ih)/2", size),
+        ],
+        ScalingPolicy::Crop => vec![
+            format!("scale={}:force_original_aspect_ratio=increase", size),
+            format!("crop={}", size),
+        ],
+        ScalingPolicy::Fit => vec![format!(
+            "scale={}:force_original_aspect_ratio=decrease:force_divisible_by=2",
+            size
+        )],
+    }
+}
+
+/// Comma-separated list, e.g. "1280x720, 1920x1080"
+pub fn format_resolutions(resolutions: &[Resolution]) -> String {
+    resolutions
+        .iter()
+        .map(Resolution::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("1920x1080".parse(), Ok(Resolution::FULL_HD));
+        assert_eq!(Resolution::new(1080, 1920).to_string(), "1080x1920");
+        assert_eq!(Resolution::HD.to_filter_arg(), "1280:720");
+
+        for invalid in ["1280by800", "1920x", "0x1080", "x", ""] {
+            assert!(
+                invalid.parse::<Resolution>().is_err(),
+                "Failed for {}",
+                invalid
+            );
+        }
+        // No substring matching: 11920x1080 is its own resolution
+        assert_ne!("11920x1080".parse(), Ok(Resolution::FULL_HD));
+    }
+
+    #[test]
+    fn test_orientation_and_aspect() {
+        assert_eq!(Resolution::FULL_HD.orientation(), Orientation::Landscape);
+        assert_eq!(
+            Resolution::new(1080, 1920).orientation(),
+            Orientation::Portrait
+        );
+        assert_eq!(
+            Resolution::new(1080, 1080).orientation(),
+            Orientation::Square
+        );
+        assert!((Resolution::FULL_HD.aspect_ratio() - 16.0 / 9.0).abs() < 1e-9);
+        assert_eq!(Resolution::new(1080, 1920).landscape(), Resolution::FULL_HD);
+        assert!(!Resolution::default().is_known());
+    }
+
+    #[test]
+    fn test_nearest_standard() {
+        let standards = [
+            Resolution::HD,
+            Resolution::FULL_HD,
+            Resolution::new(720, 1280),
+            Resolution::new(1080, 1920),
+        ];
+
+        let nearest = |w, h| Resolution::new(w, h).nearest(&standards);
+        assert_eq!(nearest(3840, 2160), Some(Resolution::FULL_HD));
+        assert_eq!(nearest(1366, 768), Some(Resolution::HD));
+        assert_eq!(nearest(2160, 3840), Some(Resolution::new(1080, 1920)));
+        assert_eq!(nearest(640, 1136), Some(Resolution::new(720, 1280)));
+        // No 4:3 standard: closest landscape size wins
+        assert_eq!(nearest(1440, 1080), Some(Resolution::FULL_HD));
+
+        assert_eq!(Resolution::FULL_HD.nearest(&[]), None);
+    }
+
+    #[test]
+    fn test_select_target_keeps_orientation_and_aspect() {
+        let preferred = [
+            Resolution::HD,
+            Resolution::FULL_HD,
+            Resolution::new(720, 1280),
+            Resolution::new(1080, 1920),
+        ];
+        let acceptable = [Resolution::new(1280, 800), Resolution::new(1440, 900)];
+        let target = |w, h| select_target(Resolution::new(w, h), &preferred, &acceptable);
+
+        assert_eq!(target(1242, 2208), Some(Resolution::new(1080, 1920)));
+        assert_eq!(target(3840, 2160), Some(Resolution::FULL_HD));
+        // 16:10 screen captures stay 16:10 via the acceptable list
+        assert_eq!(target(2560, 1600), Some(Resolution::new(1440, 900)));
+        // No 4:3 target anywhere: nearest preferred, padded or cropped
+        assert_eq!(target(1024, 768), Some(Resolution::HD));
+        assert_eq!(select_target(Resolution::FULL_HD, &[], &[]), None);
+        // Portrait footage is left alone rather than squashed into landscape
+        assert_eq!(
+            select_target(Resolution::new(1080, 1920), &[Resolution::FULL_HD], &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_scale_filters_per_policy() {
+        let source = Resolution::new(1440, 1080);
+        let target = Resolution::FULL_HD;
+
+        assert_eq!(
+            scale_filters(source, target, ScalingPolicy::Pad),
+            vec![
+                "scale=1920:1080:force_original_aspect_ratio=decrease:force_divisible_by=2",
+                "pad=1920:1080:(ow-iw)/2:(oh-ih)/2",
+            ]
+        );
+        assert_eq!(
+            scale_filters(source, target, ScalingPolicy::Crop),
+            vec![
+                "scale=1920:1080:force_original_aspect_ratio=increase",
+                "crop=1920:1080",
+            ]
+        );
+        assert_eq!(
+            scale_filters(source, target, ScalingPolicy::Fit),
+            vec!["scale=1920:1080:force_original_aspect_ratio=decrease:force_divisible_by=2"]
+        );
+
+        // Matching aspect ratios scale directly, identical sizes need nothing
+        assert_eq!(
+            scale_filters(Resolution::new(3840, 2160), target, ScalingPolicy::Pad),
+            vec!["scale=1920:1080"]
+        );
+        assert!(scale_filters(target, target, ScalingPolicy::Crop).is_empty());
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        let json = serde_json::to_string(&vec![Resolution::FULL_HD]).unwrap();
+        assert_eq!(json, r#"["1920x1080"]"#);
+
+        let parsed: Vec<Resolution> = serde_json::from_str(r#"["720x1280"]"#).unwrap();
+        assert_eq!(parsed, vec![Resolution::new(720, 1280)]);
+
+        let err = serde_json::from_str::<Resolution>(r#""1280by800""#).unwrap_err();
+        assert!(err.to_string().contains("not a WIDTHxHEIGHT resolution"));
+
+        let policy: ScalingPolicy = serde_json::from_str(r#""crop""#).unwrap();
+        assert_eq!(policy, ScalingPolicy::Crop);
+    }
+}
@@ -0,0 +1,666 @@
+//! Packet-level stream analysis
+//!
+//! This module provides:
+//! - Keyframe (GOP) interval analysis from demuxed video packets
+//! - Open/closed GOP detection from packet decode order
+//! - Variable frame rate detection from frame timestamp deltas
+//! - Audio bitrate measurement and CBR/VBR detection from packet sizes
+//!
+//! Packets are read with `ffprobe -show_packets`, which only demuxes the
+//! file, so a scan is much cheaper than decoding every frame.
+
+use crate::VideoError;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+use tracing::{debug, instrument};
+
+/// Timing and flags of one demuxed packet, in decode order
+#[derive(Debug, Clone, PartialEq)]
+pub struct PacketInfo {
+    pub pts_time: f64,
+    pub keyframe: bool,
+}
+
+/// Keyframe spacing of a video stream
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GopAnalysis {
+    pub keyframe_count: usize,
+    /// The encoder's regular GOP length, with scene-cut GOPs left out
+    pub typical_gop_seconds: f64,
+    pub min_gop_seconds: f64,
+    pub max_gop_seconds: f64,
+    pub mean_gop_seconds: f64,
+    /// False when frames after a keyframe reference pictures before it
+    pub closed_gop: bool,
+}
+
+impl GopAnalysis {
+    /// One-line summary, e.g. "2.00 s (min 1.20 s, max 2.00 s, mean 1.96 s, closed GOP)"
+    pub fn summary(&self) -> String {
+        format!(
+            "{:.2} s (min {:.2} s, max {:.2} s, mean {:.2} s, {} GOP)",
+            self.typical_gop_seconds,
+            self.min_gop_seconds,
+            self.max_gop_seconds,
+            self.mean_gop_seconds,
+            if self.closed_gop { "closed" } else { "open" }
+        )
+    }
+}
+
+/// Largest relative spread of frame intervals (standard deviation over mean)
+/// still treated as constant frame rate; timebase rounding stays well below it
+pub const VFR_MAX_JITTER: f64 = 0.10;
+
+/// Largest relative difference between `avg_frame_rate` and `r_frame_rate`
+/// treated as constant frame rate when no timestamps are available
+pub const VFR_MAX_RATE_MISMATCH: f64 = 0.01;
+
+/// Nominal and measured frame timing of a video stream
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FrameRateAnalysis {
+    /// ffprobe `r_frame_rate`, the rate the stream claims
+    pub nominal_fps: f64,
+    /// ffprobe `avg_frame_rate`, frames over duration
+    pub average_fps: f64,
+    /// Number of frame timestamps measured, 0 when none were available
+    pub frame_count: usize,
+    pub min_interval_ms: f64,
+    pub max_interval_ms: f64,
+    pub mean_interval_ms: f64,
+    /// Standard deviation of the frame intervals
+    pub jitter_ms: f64,
+    pub variable: bool,
+}
+
+impl FrameRateAnalysis {
+    /// One-line summary, e.g. "VFR: average 27.431 fps vs nominal 60 fps, frame interval 16.7–66.7 ms (jitter 9.12 ms)"
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{}: average {:.3} fps vs nominal {} fps",
+            if self.variable { "VFR" } else { "CFR" },
+            self.average_fps,
+            (self.nominal_fps * 1000.0).round() / 1000.0
+        );
+        if self.frame_count > 1 {
+            summary.push_str(&format!(
+                ", frame interval {:.1}–{:.1} ms (jitter {:.2} ms)",
+                self.min_interval_ms, self.max_interval_ms, self.jitter_ms
+            ));
+        }
+        summary
+    }
+}
+
+/// Size and timing of one demuxed audio packet
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioPacketInfo {
+    pub pts_time: f64,
+    pub size: u64,
+}
+
+/// Largest relative spread of per-second bitrate still treated as constant bitrate
+pub const CBR_MAX_VARIATION: f64 = 0.05;
+
+/// Measured bitrate and packet size statistics of an audio stream
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AudioBitrateAnalysis {
+    pub packet_count: usize,
+    /// Mean packet size in bytes
+    pub mean_packet_bytes: f64,
+    /// Variance of packet sizes in bytes²
+    pub packet_size_variance: f64,
+    /// Average bitrate over the whole stream
+    pub mean_kbps: f64,
+    /// Coefficient of variation of the bitrate across one-second windows
+    pub bitrate_variation: f64,
+    pub constant_bitrate: bool,
+}
+
+impl AudioBitrateAnalysis {
+    /// One-line summary, e.g. "CBR 320 kbps (mean 1707.0 B/packet, variance 2.1, ±0.4%)"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {:.0} kbps (mean {:.1} B/packet, variance {:.1}, ±{:.1}%)",
+            if self.constant_bitrate { "CBR" } else { "VBR" },
+            self.mean_kbps,
+            self.mean_packet_bytes,
+            self.packet_size_variance,
+            self.bitrate_variation * 100.0
+        )
+    }
+}
+
+/// Read the packets of the first video stream with ffprobe
+#[instrument]
+pub fn probe_video_packets(path: &Path) -> Result<Vec<PacketInfo>, VideoError> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "packet=pts_time,flags",
+            "-of",
+            "json",
+            path.to_str()
+                .ok_or_else(|| VideoError::InvalidPath(path.display().to_string()))?,
+        ])
+        .output()
+        .map_err(|e| VideoError::FFmpeg(format!("failed to run ffprobe: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(VideoError::FFmpeg(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| VideoError::FFmpeg(e.to_string()))?;
+
+    Ok(parse_packets(&json))
+}
+
+/// Read the packets of the first audio stream with ffprobe
+#[instrument]
+pub fn probe_audio_packets(path: &Path) -> Result<Vec<AudioPacketInfo>, VideoError> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "a:0",
+            "-show_entries",
+            "packet=pts_time,size",
+            "-of",
+            "json",
+            path.to_str()
+                .ok_or_else(|| VideoError::InvalidPath(path.display().to_string()))?,
+        ])
+        .output()
+        .map_err(|e| VideoError::FFmpeg(format!("failed to run ffprobe: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(VideoError::FFmpeg(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| VideoError::FFmpeg(e.to_string()))?;
+
+    Ok(parse_audio_packets(&json))
+}
+
+/// Extract audio packets from ffprobe `-show_packets` JSON, skipping incomplete ones
+pub fn parse_audio_packets(json: &serde_json::Value) -> Vec<AudioPacketInfo> {
+    json["packets"]
+        .as_array()
+        .map(|packets| {
+            packets
+                .iter()
+                .filter_map(|packet| {
+                    let pts_time = packet["pts_time"].as_str()?.parse::<f64>().ok()?;
+                    let size = packet["size"].as_str()?.parse::<u64>().ok()?;
+                    Some(AudioPacketInfo { pts_time, size })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Measure audio bitrate and classify it as CBR or VBR
+///
+/// Encoders with a bit reservoir (AAC) vary individual packet sizes even in
+/// CBR mode, so the classification uses the bitrate of one-second windows;
+/// the per-packet mean and variance are reported alongside it.
+pub fn analyze_audio_bitrate(packets: &[AudioPacketInfo]) -> Option<AudioBitrateAnalysis> {
+    let first = packets.iter().map(|p| p.pts_time).reduce(f64::min)?;
+    let last = packets.iter().map(|p| p.pts_time).reduce(f64::max)?;
+    let duration = last - first;
+    if packets.len() < 2 || duration <= 0.0 {
+        return None;
+    }
+
+    let sizes: Vec<f64> = packets.iter().map(|p| p.size as f64).collect();
+    let mean_packet_bytes = mean(&sizes);
+    let packet_size_variance = variance(&sizes, mean_packet_bytes);
+
+    // The last packet's duration is unknown; extend by one mean packet interval
+    let total_duration = duration * packets.len() as f64 / (packets.len() - 1) as f64;
+    let total_bytes: f64 = sizes.iter().sum();
+    let mean_kbps = total_bytes * 8.0 / total_duration / 1000.0;
+
+    // Complete one-second windows only; the final partial window is dropped
+    let complete_windows = duration.floor() as usize;
+    let mut window_bytes = vec![0.0; complete_windows];
+    for packet in packets {
+        let window = ((packet.pts_time - first).floor()) as usize;
+        if window < complete_windows {
+            window_bytes[window] += packet.size as f64;
+        }
+    }
+    let bitrate_variation = if window_bytes.len() >= 2 {
+        let window_mean = mean(&window_bytes);
+        if window_mean > 0.0 {
+            variance(&window_bytes, window_mean).sqrt() / window_mean
+        } else {
+            0.0
+        }
+    } else {
+        // Too short to measure across windows; fall back to packet sizes
+        if mean_packet_bytes > 0.0 {
+            packet_size_variance.sqrt() / mean_packet_bytes
+        } else {
+            0.0
+        }
+    };
+
+    let analysis = AudioBitrateAnalysis {
+        packet_count: packets.len(),
+        mean_packet_bytes,
+        packet_size_variance,
+        mean_kbps,
+        bitrate_variation,
+        constant_bitrate: bitrate_variation <= CBR_MAX_VARIATION,
+    };
+    debug!(?analysis, "audio bitrate analysis");
+    Some(analysis)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn variance(values: &[f64], mean: f64) -> f64 {
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+}
+
+/// Extract packets from ffprobe `-show_packets` JSON, skipping ones without a timestamp
+pub fn parse_packets(json: &serde_json::Value) -> Vec<PacketInfo> {
+    json["packets"]
+        .as_array()
+        .map(|packets| {
+            packets
+                .iter()
+                .filter_map(|packet| {
+                    let pts_time = packet["pts_time"].as_str()?.parse::<f64>().ok()?;
+                    let keyframe = packet["flags"]
+                        .as_str()
+                        .map(|flags| flags.starts_with('K'))
+                        .unwrap_or(false);
+                    Some(PacketInfo { pts_time, keyframe })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// GOP lengths within this many seconds of each other count as the same length
+const GOP_LENGTH_TOLERANCE: f64 = 0.05;
+
+/// The regular GOP length among keyframe intervals. Encoders insert a keyframe
+/// every `-g` frames and at scene cuts; a scene cut ends a GOP early at an
+/// arbitrary length, while regular GOPs all have the same length. The most
+/// common length is therefore the configured interval. When no length repeats,
+/// every GOP was ended by a scene cut and the longest is the best estimate.
+fn typical_gop_length(intervals: &[f64]) -> f64 {
+    let mut sorted = intervals.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    // (count, mean) of each run of similar lengths; ties go to the longer run
+    let mut best = (0, 0.0);
+    let mut start = 0;
+    while start < sorted.len() {
+        let end = sorted[start..]
+            .iter()
+            .position(|length| length - sorted[start] > GOP_LENGTH_TOLERANCE)
+            .map_or(sorted.len(), |offset| start + offset);
+        let count = end - start;
+        if count >= best.0 {
+            best = (count, sorted[start..end].iter().sum::<f64>() / count as f64);
+        }
+        start = end;
+    }
+    best.1
+}
+
+/// Measure keyframe spacing from packets in decode order
+pub fn analyze_gop(packets: &[PacketInfo]) -> Option<GopAnalysis> {
+    let mut keyframe_times: Vec<f64> = packets
+        .iter()
+        .filter(|p| p.keyframe)
+        .map(|p| p.pts_time)
+        .collect();
+    if keyframe_times.is_empty() {
+        return None;
+    }
+    keyframe_times.sort_by(|a, b| a.total_cmp(b));
+
+    let mut intervals: Vec<f64> = keyframe_times.windows(2).map(|w| w[1] - w[0]).collect();
+    if intervals.is_empty() {
+        // A single keyframe: the whole stream is one GOP
+        let last = packets
+            .iter()
+            .map(|p| p.pts_time)
+            .fold(keyframe_times[0], f64::max);
+        intervals.push(last - keyframe_times[0]);
+    }
+
+    // Open GOP: a packet decoded after a (non-initial) keyframe is displayed before it
+    let mut closed_gop = true;
+    let mut current_keyframe: Option<f64> = None;
+    for (index, packet) in packets.iter().enumerate() {
+        if packet.keyframe {
+            current_keyframe = (index > 0).then_some(packet.pts_time);
+        } else if let Some(keyframe_time) = current_keyframe {
+            if packet.pts_time < keyframe_time - 1e-6 {
+                closed_gop = false;
+                break;
+            }
+        }
+    }
+
+    let analysis = GopAnalysis {
+        keyframe_count: keyframe_times.len(),
+        typical_gop_seconds: typical_gop_length(&intervals),
+        min_gop_seconds: intervals.iter().copied().fold(f64::INFINITY, f64::min),
+        max_gop_seconds: intervals.iter().copied().fold(0.0, f64::max),
+        mean_gop_seconds: intervals.iter().sum::<f64>() / intervals.len() as f64,
+        closed_gop,
+    };
+    debug!(?analysis, "GOP analysis");
+    Some(analysis)
+}
+
+/// Compare nominal and average frame rates and measure the spread of frame
+/// intervals from packet timestamps (sorted into presentation order)
+pub fn analyze_frame_timing(
+    packets: &[PacketInfo],
+    nominal_fps: f64,
+    average_fps: f64,
+) -> Option<FrameRateAnalysis> {
+    if nominal_fps <= 0.0 {
+        return None;
+    }
+
+    let mut timestamps: Vec<f64> = packets.iter().map(|p| p.pts_time).collect();
+    timestamps.sort_by(|a, b| a.total_cmp(b));
+    let intervals: Vec<f64> = timestamps
+        .windows(2)
+        .map(|w| (w[1] - w[0]) * 1000.0)
+        .filter(|interval| *interval > 0.0)
+        .collect();
+
+    let rate_mismatch = average_fps > 0.0
+        && (average_fps - nominal_fps).abs() / nominal_fps > VFR_MAX_RATE_MISMATCH;
+
+    let analysis = if intervals.len() < 2 {
+        FrameRateAnalysis {
+            nominal_fps,
+            average_fps,
+            frame_count: 0,
+            min_interval_ms: 0.0,
+            max_interval_ms: 0.0,
+            mean_interval_ms: 0.0,
+            jitter_ms: 0.0,
+            variable: rate_mismatch,
+        }
+    } else {
+        let mean_interval = mean(&intervals);
+        let jitter = variance(&intervals, mean_interval).sqrt();
+        FrameRateAnalysis {
+            nominal_fps,
+            average_fps,
+            frame_count: timestamps.len(),
+            min_interval_ms: intervals.iter().copied().fold(f64::INFINITY, f64::min),
+            max_interval_ms: intervals.iter().copied().fold(0.0, f64::max),
+            mean_interval_ms: mean_interval,
+            jitter_ms: jitter,
+            // Timestamps are the ground truth; r_frame_rate vs avg_frame_rate
+            // also differs for CFR field-rate (interlaced) streams
+            variable: jitter / mean_interval > VFR_MAX_JITTER,
+        }
+    };
+    debug!(?analysis, "frame timing analysis");
+    Some(analysis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packets for a closed-GOP stream with keyframes at the given times
+    fn packets(fps: f64, duration: f64, keyframes: &[f64]) -> Vec<PacketInfo> {
+        let frames = (duration * fps) as usize;
+        (0..frames)
+            .map(|i| {
+                let pts_time = i as f64 / fps;
+                PacketInfo {
+                    pts_time,
+                    keyframe: keyframes.iter().any(|k| (k - pts_time).abs() < 1e-9),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_gop_intervals() {
+        let analysis = analyze_gop(&packets(30.0, 10.0, &[0.0, 2.0, 4.0, 5.0, 7.0, 9.0])).unwrap();
+
+        assert_eq!(analysis.keyframe_count, 6);
+        assert!((analysis.min_gop_seconds - 1.0).abs() < 1e-9);
+        assert!((analysis.max_gop_seconds - 2.0).abs() < 1e-9);
+        assert!((analysis.mean_gop_seconds - 1.8).abs() < 1e-9);
+        assert!((analysis.typical_gop_seconds - 2.0).abs() < 1e-9);
+        assert!(analysis.closed_gop);
+        assert_eq!(
+            analysis.summary(),
+            "2.00 s (min 1.00 s, max 2.00 s, mean 1.80 s, closed GOP)"
+        );
+    }
+
+    #[test]
+    fn test_typical_gop_ignores_scene_cuts() {
+        // 2 s GOPs with scene cuts ending some early at irregular lengths
+        let analysis = analyze_gop(&packets(
+            30.0,
+            12.0,
+            &[0.0, 2.0, 2.7, 4.7, 6.7, 7.2, 9.2, 11.0],
+        ))
+        .unwrap();
+        assert!((analysis.typical_gop_seconds - 2.0).abs() < 1e-6);
+
+        // Mostly 1 s GOPs hidden behind one long GOP
+        let analysis =
+            analyze_gop(&packets(30.0, 10.0, &[0.0, 1.0, 2.0, 3.0, 4.0, 8.0, 9.0])).unwrap();
+        assert!((analysis.max_gop_seconds - 4.0).abs() < 1e-6);
+        assert!((analysis.typical_gop_seconds - 1.0).abs() < 1e-6);
+
+        // No repeated length: every GOP ended at a scene cut
+        assert!((typical_gop_length(&[0.4, 1.3, 0.9]) - 1.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_single_keyframe_and_no_keyframes() {
+        let analysis = analyze_gop(&packets(25.0, 4.0, &[0.0])).unwrap();
+        assert_eq!(analysis.keyframe_count, 1);
+        assert!((analysis.max_gop_seconds - 3.96).abs() < 1e-9);
+
+        assert!(analyze_gop(&packets(25.0, 4.0, &[])).is_none());
+    }
+
+    #[test]
+    fn test_open_gop_detection() {
+        // Decode order I P B B I B B P: the B-frames after the second I
+        // are displayed before it
+        let decode_order = [
+            (0.0, true),
+            (0.3, false),
+            (0.1, false),
+            (0.2, false),
+            (0.6, true),
+            (0.4, false),
+            (0.5, false),
+            (0.9, false),
+        ];
+        let packets: Vec<PacketInfo> = decode_order
+            .iter()
+            .map(|&(pts_time, keyframe)| PacketInfo { pts_time, keyframe })
+            .collect();
+
+        let analysis = analyze_gop(&packets).unwrap();
+        assert!(!analysis.closed_gop);
+        assert!((analysis.max_gop_seconds - 0.6).abs() < 1e-9);
+    }
+
+    /// AAC packets (1024 samples at 48 kHz) with sizes produced by `size_at(index)`
+    fn audio_packets(seconds: f64, size_at: impl Fn(usize) -> u64) -> Vec<AudioPacketInfo> {
+        let packet_duration = 1024.0 / 48000.0;
+        let count = (seconds / packet_duration) as usize;
+        (0..count)
+            .map(|i| AudioPacketInfo {
+                pts_time: i as f64 * packet_duration,
+                size: size_at(i),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_audio_cbr_detection() {
+        // 320 kbps CBR: 853.3 bytes per packet, reservoir jitter averages out
+        let packets = audio_packets(10.0, |i| if i % 3 == 0 { 854 } else { 853 });
+        let analysis = analyze_audio_bitrate(&packets).unwrap();
+
+        assert!(analysis.constant_bitrate);
+        assert!((analysis.mean_kbps - 320.0).abs() < 1.0);
+        assert!((analysis.mean_packet_bytes - 853.3).abs() < 0.1);
+        assert!(analysis.packet_size_variance < 1.0);
+        assert!(analysis.summary().starts_with("CBR 320 kbps"));
+    }
+
+    #[test]
+    fn test_audio_vbr_detection() {
+        // Quiet first half, loud second half
+        let packets = audio_packets(10.0, |i| if i < 234 { 300 } else { 1200 });
+        let analysis = analyze_audio_bitrate(&packets).unwrap();
+
+        assert!(!analysis.constant_bitrate);
+        assert!(analysis.bitrate_variation > CBR_MAX_VARIATION);
+        assert!(analysis.packet_size_variance > 100_000.0);
+        assert!(analysis.summary().starts_with("VBR"));
+
+        assert!(analyze_audio_bitrate(&[]).is_none());
+    }
+
+    #[test]
+    fn test_parse_audio_packets() {
+        let json = serde_json::json!({
+            "packets": [
+                { "pts_time": "0.000000", "size": "853" },
+                { "pts_time": "0.021333", "size": "854" },
+                { "pts_time": "N/A", "size": "12" }
+            ]
+        });
+        assert_eq!(
+            parse_audio_packets(&json),
+            vec![
+                AudioPacketInfo {
+                    pts_time: 0.0,
+                    size: 853
+                },
+                AudioPacketInfo {
+                    pts_time: 0.021333,
+                    size: 854
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frame_timing_cfr() {
+        // 29.97 fps in a millisecond timebase: intervals alternate 33/34 ms
+        let packets: Vec<PacketInfo> = (0..300)
+            .map(|i| PacketInfo {
+                pts_time: (i as f64 * 1001.0 / 30.0).round() / 1000.0,
+                keyframe: i % 60 == 0,
+            })
+            .collect();
+
+        let analysis = analyze_frame_timing(&packets, 30000.0 / 1001.0, 30000.0 / 1001.0).unwrap();
+        assert!(!analysis.variable);
+        assert_eq!(analysis.frame_count, 300);
+        assert!((analysis.mean_interval_ms - 33.367).abs() < 0.01);
+        assert!(analysis
+            .summary()
+            .starts_with("CFR: average 29.970 fps vs nominal 29.97 fps"));
+    }
+
+    #[test]
+    fn test_frame_timing_vfr() {
+        // Screen recording that drops to 15 fps while the screen is static
+        let mut pts_time = 0.0;
+        let packets: Vec<PacketInfo> = (0..300)
+            .map(|i| {
+                let packet = PacketInfo {
+                    pts_time,
+                    keyframe: i == 0,
+                };
+                pts_time += if (100..200).contains(&i) {
+                    1.0 / 15.0
+                } else {
+                    1.0 / 60.0
+                };
+                packet
+            })
+            .collect();
+
+        let analysis = analyze_frame_timing(&packets, 60.0, 33.4).unwrap();
+        assert!(analysis.variable);
+        assert!((analysis.min_interval_ms - 16.667).abs() < 0.01);
+        assert!((analysis.max_interval_ms - 66.667).abs() < 0.01);
+        assert!(analysis
+            .summary()
+            .starts_with("VFR: average 33.400 fps vs nominal 60 fps"));
+
+        // Without timestamps the rate mismatch alone decides
+        let analysis = analyze_frame_timing(&[], 60.0, 33.4).unwrap();
+        assert!(analysis.variable);
+        assert_eq!(
+            analysis.summary(),
+            "VFR: average 33.400 fps vs nominal 60 fps"
+        );
+        assert!(!analyze_frame_timing(&[], 30.0, 30.0).unwrap().variable);
+        assert!(analyze_frame_timing(&[], 0.0, 30.0).is_none());
+    }
+
+    #[test]
+    fn test_parse_packets() {
+        let json = serde_json::json!({
+            "packets": [
+                { "pts_time": "0.000000", "flags": "K__" },
+                { "pts_time": "0.033367", "flags": "___" },
+                { "pts_time": "N/A", "flags": "___" },
+                { "flags": "K_" }
+            ]
+        });
+
+        let packets = parse_packets(&json);
+        assert_eq!(
+            packets,
+            vec![
+                PacketInfo {
+                    pts_time: 0.0,
+                    keyframe: true
+                },
+                PacketInfo {
+                    pts_time: 0.033367,
+                    keyframe: false
+                },
+            ]
+        );
+        assert!(parse_packets(&serde_json::json!({})).is_empty());
+    }
+}